@@ -6,9 +6,11 @@ use core::mem::size_of;
 use memoffset::offset_of;
 use sa::static_assert;
 
+#[cfg(feature = "alloc")]
+use crate::diff::{counterpart_value, EntryLookup};
 use crate::{
     util::{hash_name, read_u32, Name},
-    Error, Result, TableIndex,
+    EntryStatus, Error, Precedence, Result, TableIndex,
 };
 
 /// Constant representing the magic of an RESTBL file
@@ -24,9 +26,20 @@ pub struct Header {
     name_table_count: u32,
 }
 static_assert!(Header::FULL_SIZE == 0x16);
+static_assert!(size_of::<Name>() == 0xA0);
 
 impl Header {
-    const FULL_SIZE: usize = size_of::<Header>() + MAGIC.len();
+    pub(crate) const FULL_SIZE: usize = size_of::<Header>() + MAGIC.len();
+
+    /// Construct a version 1 header for a table with the given entry counts
+    pub(crate) fn with_counts(crc_table_count: u32, name_table_count: u32) -> Self {
+        Self {
+            version: 1,
+            string_block_size: size_of::<Name>() as u32,
+            crc_table_count,
+            name_table_count,
+        }
+    }
 
     #[inline(always)]
     pub fn version(&self) -> u32 {
@@ -48,9 +61,21 @@ impl Header {
         self.name_table_count
     }
 
-    /// Attempt to parse the RESTBL header from a slice
-    fn read(data: &[u8]) -> Result<Self> {
-        if data.len() < Self::FULL_SIZE {
+    /// Parse just the header at the start of an RSTB file, without checking
+    /// that the rest of the data matches it, for cheaply reading the version
+    /// and entry counts of many files. The magic is still validated. Use
+    /// [`ResTblReader::new`] to validate the whole layout.
+    #[inline]
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        Self::read(data)
+    }
+
+    /// Parse the RESTBL header from a slice without checking the version or
+    /// string block size, for readers of nonstandard layouts
+    fn read_any_layout(data: &[u8]) -> Result<Self> {
+        if data.starts_with(&crate::util::ZSTD_MAGIC) {
+            Err(Error::Compressed)
+        } else if data.len() < Self::FULL_SIZE {
             Err(Error::InsufficientData(data.len(), "0x16 bytes for header"))
         } else if &data[..MAGIC.len()] != MAGIC {
             Err(Error::InvalidMagic(
@@ -69,6 +94,28 @@ impl Header {
         }
     }
 
+    /// Attempt to parse the RESTBL header from a slice. Fails with
+    /// [`Error::UnsupportedHeader`] for any layout other than version 1 with
+    /// 160-byte names, which is the only one TOTK has used so far.
+    ///
+    /// The version is not stored on owned tables, and the writers always emit
+    /// a version 1 header. Since no other version can be read, every table
+    /// still round-trips unchanged; a new layout would need its version kept
+    /// alongside the table to be written back.
+    pub fn read(data: &[u8]) -> Result<Self> {
+        let header = Self::read_any_layout(data)?;
+        // Only the version 1 layout with 160-byte names is known. Any other
+        // header may place its fields or entries differently, so it must be
+        // rejected rather than read as version 1. Support for a new layout
+        // belongs in a new arm here.
+        match (header.version, header.string_block_size) {
+            (1, 0xA0) => Ok(header),
+            (version, string_block_size) => {
+                Err(Error::UnsupportedHeader(version, string_block_size))
+            }
+        }
+    }
+
     /// Attempt to serialize the RESTBL header to a writer
     pub fn write(self, buffer: &mut [u8]) {
         buffer[..MAGIC.len()].copy_from_slice(MAGIC);
@@ -80,15 +127,26 @@ impl Header {
 }
 
 /// Represents a RESTBL hash entry
-#[repr(C)]
+///
+/// The struct is packed so that it has an alignment of 1, letting the hash
+/// table be viewed in place by [`ResTblReader::hash_entries_slice`] even
+/// though it starts at the unaligned offset 0x16.
+#[repr(C, packed)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct HashEntry {
     hash: u32,
     value: u32,
 }
 static_assert!(size_of::<HashEntry>() == 0x8);
+static_assert!(core::mem::align_of::<HashEntry>() == 1);
 
 impl HashEntry {
+    /// Construct a hash entry
+    #[inline(always)]
+    pub const fn new(hash: u32, value: u32) -> Self {
+        Self { hash, value }
+    }
+
     /// Attempt to parse a RESTBL hash entry from a slice
     pub fn read(buffer: &[u8]) -> Result<Self> {
         if buffer.len() < size_of::<HashEntry>() {
@@ -133,6 +191,12 @@ pub struct NameEntry {
 static_assert!(size_of::<NameEntry>() == 0xa4);
 
 impl NameEntry {
+    /// Construct a name entry
+    #[inline(always)]
+    pub const fn new(name: Name, value: u32) -> Self {
+        Self { name, value }
+    }
+
     /// Attempt to parse a RESTBL name entry from a slice
     pub fn read(buffer: &[u8]) -> Result<Self> {
         if buffer.len() < size_of::<NameEntry>() {
@@ -148,6 +212,30 @@ impl NameEntry {
         }
     }
 
+    /// Parse a name entry whose name field is `width` bytes wide rather than
+    /// 160, for nonstandard layouts. Fails with [`Error::NameTooLong`] if a
+    /// name in a wider field does not fit in a [`Name`].
+    pub(crate) fn read_with_width(buffer: &[u8], width: usize) -> Result<Self> {
+        if width == size_of::<Name>() {
+            return Self::read(buffer);
+        }
+        if buffer.len() < width + 4 {
+            return Err(Error::InsufficientData(
+                buffer.len(),
+                "name width + 4 bytes for NameEntry",
+            ));
+        }
+        let field = &buffer[..width];
+        let len = field.iter().position(|c| *c == 0).unwrap_or(width);
+        if len >= size_of::<Name>() {
+            return Err(Error::NameTooLong(len));
+        }
+        Ok(Self {
+            name: Name::try_from(&field[..len])?,
+            value: read_u32(buffer, Some(width))?,
+        })
+    }
+
     /// Serialize a RESTBL name entry to a buffer
     pub fn write(self, buffer: &mut [u8]) {
         buffer[..size_of::<Self>()].copy_from_slice(
@@ -174,6 +262,7 @@ pub struct ResTblReader<'a> {
     #[cfg(not(feature = "alloc"))]
     data: &'a [u8],
     header: Header,
+    name_width: usize,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -182,6 +271,70 @@ pub enum TableEntry {
     Name(NameEntry),
 }
 
+impl TableEntry {
+    /// Get the RSTB value of the entry
+    #[inline(always)]
+    pub fn value(&self) -> u32 {
+        match self {
+            Self::Hash(entry) => entry.value(),
+            Self::Name(entry) => entry.value(),
+        }
+    }
+
+    /// Compare entries by their position in a serialized table: every hash
+    /// entry comes before every name entry, hash entries are ordered by
+    /// hash, and name entries by name. Entries with the same key are ordered
+    /// by value. Sorting with this comparator gives the order written by
+    /// [`ResourceSizeTable::to_binary`](crate::ResourceSizeTable::to_binary).
+    pub fn cmp_file_order(&self, other: &Self) -> core::cmp::Ordering {
+        match (self, other) {
+            (Self::Hash(a), Self::Hash(b)) => (a.hash, a.value).cmp(&(b.hash, b.value)),
+            (Self::Name(a), Self::Name(b)) => (a.name, a.value).cmp(&(b.name, b.value)),
+            (Self::Hash(_), Self::Name(_)) => core::cmp::Ordering::Less,
+            (Self::Name(_), Self::Hash(_)) => core::cmp::Ordering::Greater,
+        }
+    }
+}
+
+/// Entries are ordered by value, so that a `BinaryHeap` of entries pops the
+/// largest value first. Entries with the same value are ordered by key as by
+/// [`TableEntry::cmp_file_order`]. Use that method to sort entries by key.
+impl Ord for TableEntry {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.value()
+            .cmp(&other.value())
+            .then_with(|| self.cmp_file_order(other))
+    }
+}
+
+impl PartialOrd for TableEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Aggregate result of [`ResTblReader::lookup_all`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LookupSummary<'i> {
+    /// The number of keys found in the table
+    pub hits: usize,
+    /// The number of keys not found in the table
+    pub misses: usize,
+    /// The keys not found, in the order they were given
+    #[cfg(feature = "alloc")]
+    pub missing: alloc::vec::Vec<TableIndex<'i>>,
+    #[cfg(not(feature = "alloc"))]
+    _marker: core::marker::PhantomData<TableIndex<'i>>,
+}
+
+impl LookupSummary<'_> {
+    /// Check if every key was found
+    #[inline(always)]
+    pub fn all_found(&self) -> bool {
+        self.misses == 0
+    }
+}
+
 struct HashTableIndex(usize);
 struct NameTableIndex(usize);
 
@@ -202,13 +355,13 @@ impl<'a> Iterator for ResTblIterator<'a> {
         } else {
             let start = self.table.name_table_offset()
                 + (self.index - self.table.header.crc_table_count as usize)
-                    * size_of::<NameEntry>();
-            let end = start + size_of::<NameEntry>();
-            if end > self.table.data.len() {
+                    * self.table.name_entry_size();
+            let end = start + self.table.name_entry_size();
+            if end > self.table.table_size() {
                 None
             } else {
-                let data = &self.table.data[start..start + size_of::<NameEntry>()];
-                let entry = NameEntry::read(data).ok();
+                let data = &self.table.data[start..end];
+                let entry = NameEntry::read_with_width(data, self.table.name_width).ok();
                 self.index += 1;
                 entry.map(TableEntry::Name)
             }
@@ -223,21 +376,186 @@ type Buffer<'a> = &'a [u8];
 
 impl<'a> ResTblReader<'a> {
     /// Construct a new RSTB parser
+    ///
+    /// The table's size is computed from the entry counts in its header, and
+    /// nothing past that size is ever read, so the data may be a sub-slice of
+    /// a larger buffer with unrelated bytes after the table, such as an RSTB
+    /// file found inside a SARC archive. Use [`ResTblReader::new_strict`] to
+    /// reject such trailing data instead.
+    ///
+    /// Data too short for a header fails with [`Error::InsufficientData`].
+    /// Data starting with a zstd frame, such as the contents of a `.zs` file
+    /// which was not decompressed, fails with [`Error::Compressed`].
     pub fn new<D: Into<Buffer<'a>>>(data: D) -> Result<Self> {
         fn inner(data: Buffer<'_>) -> Result<ResTblReader<'_>> {
-            let header = Header::read(&data[..Header::FULL_SIZE])?;
-            let expected_size = Header::FULL_SIZE
-                + header.crc_table_count as usize * size_of::<HashEntry>()
-                + header.name_table_count as usize * size_of::<NameEntry>();
-            if data.len() < expected_size {
-                Err(Error::InvalidTableSize(data.len(), expected_size))
-            } else {
-                Ok(ResTblReader { data, header })
-            }
+            let header = Header::read(&data)?;
+            ResTblReader::with_header(data, header, size_of::<Name>())
         }
         inner(data.into())
     }
 
+    /// Construct a new RSTB parser as by [`ResTblReader::new`], but first
+    /// reject a header claiming more than `max_entries` hash and name entries
+    /// combined with [`Error::TooManyEntries`]. Use this for untrusted input,
+    /// such as files uploaded to a server, before building an owned table
+    /// with [`ResourceSizeTable::from_parser`](crate::ResourceSizeTable::from_parser),
+    /// which allocates for every entry.
+    pub fn new_with_limits<D: Into<Buffer<'a>>>(data: D, max_entries: usize) -> Result<Self> {
+        ReaderBuilder::new().max_entries(max_entries).build(data)
+    }
+
+    /// Construct a new RSTB parser for a nonstandard layout whose name entries
+    /// have a `width`-byte name field instead of the usual 160 bytes. If
+    /// `width` is `None`, the header's string block size is used. The header's
+    /// version and string block size are otherwise not checked, unlike
+    /// [`ResTblReader::new`], which should be preferred for game files.
+    ///
+    /// Names from a field wider than 160 bytes must still fit in a [`Name`];
+    /// an entry with a longer name ends iteration, and fails any lookup which
+    /// reaches it, like an entry with invalid UTF-8. Fails with
+    /// [`Error::UnsupportedHeader`] if the width is 0.
+    pub fn with_name_width<D: Into<Buffer<'a>>>(data: D, width: Option<usize>) -> Result<Self> {
+        fn inner(data: Buffer<'_>, width: Option<usize>) -> Result<ResTblReader<'_>> {
+            let header = Header::read_any_layout(&data)?;
+            let width = width.unwrap_or(header.string_block_size as usize);
+            if width == 0 {
+                return Err(Error::UnsupportedHeader(header.version, 0));
+            }
+            ResTblReader::with_header(data, header, width)
+        }
+        inner(data.into(), width)
+    }
+
+    fn with_header(data: Buffer<'a>, header: Header, name_width: usize) -> Result<Self> {
+        // Huge counts could overflow on 32-bit targets, wrapping to a size
+        // small enough to pass the check below
+        let expected_size = (header.crc_table_count as usize)
+            .checked_mul(size_of::<HashEntry>())
+            .and_then(|crc_size| {
+                (header.name_table_count as usize)
+                    .checked_mul(name_width.checked_add(4)?)?
+                    .checked_add(crc_size)?
+                    .checked_add(Header::FULL_SIZE)
+            })
+            .unwrap_or(usize::MAX);
+        if data.len() < expected_size {
+            Err(Error::InvalidTableSize(data.len(), expected_size))
+        } else {
+            Ok(ResTblReader {
+                data,
+                header,
+                name_width,
+            })
+        }
+    }
+
+    /// Parse a series of tables stored back to back in one buffer, each
+    /// starting immediately after the end of the previous one as computed from
+    /// its header. Each reader covers only its own table. Iteration stops
+    /// after the last table ends exactly at the end of the buffer, or after
+    /// the first error, such as a malformed header or a table cut short.
+    pub fn iter_tables(data: &'a [u8]) -> impl Iterator<Item = Result<ResTblReader<'a>>> {
+        let mut rest = data;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let table = ResTblReader::new(rest)
+                .map(|reader| reader.table_size())
+                .and_then(|size| {
+                    let (table, next) = rest.split_at(size);
+                    rest = next;
+                    ResTblReader::new(table)
+                });
+            if table.is_err() {
+                rest = &[];
+            }
+            Some(table)
+        })
+    }
+
+    /// Detach the parser from the lifetime of its data, copying the data into
+    /// an owned buffer if it is borrowed. Like [`Cow::into_owned`], this does
+    /// nothing if the parser already owns its data.
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> ResTblReader<'static> {
+        ResTblReader {
+            data: Cow::Owned(self.data.into_owned()),
+            header: self.header,
+            name_width: self.name_width,
+        }
+    }
+
+    /// Construct a new RSTB parser, rejecting any data after the end of the
+    /// table other than zero padding. This catches accidentally concatenated
+    /// or otherwise corrupted files which [`ResTblReader::new`] tolerates.
+    pub fn new_strict<D: Into<Buffer<'a>>>(data: D) -> Result<Self> {
+        let reader = Self::new(data)?;
+        let trailing = &reader.data[reader.table_size()..];
+        if trailing.iter().any(|b| *b != 0) {
+            Err(Error::TrailingData(trailing.len()))
+        } else {
+            Ok(reader)
+        }
+    }
+
+    /// Get the number of bytes in the buffer beyond the end of the last entry,
+    /// such as alignment padding
+    #[inline(always)]
+    pub fn trailing_len(&self) -> usize {
+        self.data.len() - self.table_size()
+    }
+
+    /// Confirm that the header and body agree: iterating the table yields
+    /// exactly as many entries as the header claims. Fails with
+    /// [`Error::EntryCountMismatch`] if a name entry cannot be read, which
+    /// would otherwise silently end iteration early. Data too short for the
+    /// claimed counts is already rejected by [`ResTblReader::new`].
+    pub fn verify_counts(&self) -> Result<()> {
+        let count = self.iter().count();
+        if count != self.len() {
+            return Err(Error::EntryCountMismatch(self.len(), count));
+        }
+        Ok(())
+    }
+
+    /// Check that both tables are in strictly ascending order of key, as in
+    /// the game's files, failing with [`Error::Unsorted`] otherwise. Lookups
+    /// use binary searches, so they can miss entries in an unsorted table.
+    /// Fails with the entry's error if a name entry cannot be read.
+    pub fn verify_sorted(&self) -> Result<()> {
+        let mut last = None;
+        for entry in self.hash_entries() {
+            if last.is_some_and(|last| last >= entry.hash) {
+                return Err(Error::Unsorted);
+            }
+            last = Some(entry.hash);
+        }
+        let mut last = None;
+        for i in 0..self.header.name_table_count as usize {
+            let name = self.parse_name_entry(NameTableIndex(i))?.name;
+            if last.is_some_and(|last| last >= name) {
+                return Err(Error::Unsorted);
+            }
+            last = Some(name);
+        }
+        Ok(())
+    }
+
+    /// Check that every name entry holds valid UTF-8, failing with
+    /// [`Error::InvalidName`] and the index within the name table of the first
+    /// which does not. Iteration otherwise stops silently at such an entry.
+    pub fn validate_names(&self) -> Result<()> {
+        let offset = self.name_table_offset();
+        for i in 0..self.header.name_table_count as usize {
+            let start = offset + i * self.name_entry_size();
+            let name = &self.data[start..start + self.name_width];
+            let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            core::str::from_utf8(&name[..len]).map_err(|e| Error::InvalidName(i, e))?;
+        }
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn header(&self) -> &Header {
         &self.header
@@ -254,6 +572,26 @@ impl<'a> ResTblReader<'a> {
         self.len() == 0
     }
 
+    /// Get the hash table as a slice borrowed directly from the backing buffer,
+    /// sorted by hash as in the game's files, for callers who want to run
+    /// their own search over it. This copies nothing.
+    ///
+    /// [`HashEntry`] is packed, so the slice has no alignment requirement, but
+    /// reading its fields may compile to unaligned loads. The fields are
+    /// stored little-endian and viewed in place without byte swapping, so this
+    /// is only available on little-endian targets.
+    #[cfg(target_endian = "little")]
+    pub fn hash_entries_slice(&self) -> &[HashEntry] {
+        let count = self.header.crc_table_count as usize;
+        let region = &self.data[Header::FULL_SIZE..self.name_table_offset()];
+        debug_assert_eq!(region.len(), count * size_of::<HashEntry>());
+        // SAFETY: The region is in bounds because the table size was checked in
+        // `new()`, and holds exactly `count` entries. `HashEntry` has an
+        // alignment of 1, so any pointer into the buffer is suitably aligned,
+        // and any 8 bytes are a valid pair of `u32` values.
+        unsafe { core::slice::from_raw_parts(region.as_ptr().cast::<HashEntry>(), count) }
+    }
+
     /// SAFETY: This involves two unsafe operations, `core::mem::transmute` and
     /// unchecked slice-to-array. They are perfectly sound, however. The slice
     /// conversion is sound because the size of the slice and the size of the
@@ -281,6 +619,10 @@ impl<'a> ResTblReader<'a> {
 
     /// Check if the specified hash or resource name is present in the table.
     /// Checks the name table first (if applicable) and then the hash table.
+    ///
+    /// A resource name missing from the name table falls back to a lookup of
+    /// its hash in the hash table. Use [`ResTblReader::contains_strict`] to
+    /// disable the fallback.
     pub fn contains<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
         fn inner(tbl: &ResTblReader, needle: TableIndex) -> bool {
             match needle {
@@ -298,6 +640,17 @@ impl<'a> ResTblReader<'a> {
 
     /// Returns the RSTB value for the specified hash or resource name if present.
     /// Checks the name table first (if applicable) and then the hash table.
+    ///
+    /// A resource name missing from the name table falls back to a lookup of
+    /// its hash in the hash table. Use [`ResTblReader::get_strict`] to disable
+    /// the fallback.
+    ///
+    /// If a resource name matches both a name entry and a hash entry with
+    /// different values, the name entry's value is returned. The game's own
+    /// tables never contain such collisions, but edited ones may. This
+    /// precedence is guaranteed, and shared by the readonly parser and the
+    /// owned table. Use [`get_with_precedence`](Self::get_with_precedence) to
+    /// prefer the hash entry instead.
     pub fn get<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
         fn inner(tbl: &ResTblReader, needle: TableIndex) -> Option<u32> {
             match needle {
@@ -313,32 +666,167 @@ impl<'a> ResTblReader<'a> {
         inner(self, needle.into())
     }
 
+    /// Returns the RSTB value for the specified hash or resource name, or
+    /// `default` if it is not present. Resolves the needle the same way as
+    /// [`get`](Self::get).
+    pub fn get_or<'i, I: Into<TableIndex<'i>>>(&self, needle: I, default: u32) -> u32 {
+        self.get(needle).unwrap_or(default)
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name,
+    /// resolving a collision between a name entry and a hash entry for the
+    /// same resource name with the given precedence. Otherwise this is the
+    /// same as [`get`](Self::get), which uses [`Precedence::NameFirst`]. A
+    /// hash is only ever looked up in the hash table.
+    pub fn get_with_precedence<'i, I: Into<TableIndex<'i>>>(
+        &self,
+        needle: I,
+        precedence: Precedence,
+    ) -> Option<u32> {
+        fn inner(tbl: &ResTblReader, needle: TableIndex, precedence: Precedence) -> Option<u32> {
+            match (needle, precedence) {
+                (TableIndex::StringIndex(name), Precedence::HashFirst) => tbl
+                    .get_strict(hash_name(&name))
+                    .or_else(|| tbl.get_strict(TableIndex::StringIndex(name))),
+                (needle, _) => tbl.get(needle),
+            }
+        }
+        inner(self, needle.into(), precedence)
+    }
+
+    /// Returns whether the specified hash or resource name has an entry, and
+    /// its value if so. Resolves the needle the same way as
+    /// [`get`](Self::get).
+    pub fn status<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> EntryStatus {
+        self.get(needle).into()
+    }
+
+    /// Look up many keys at once, such as every file in a mod, counting how
+    /// many are present. Keys are resolved the same way as by
+    /// [`get`](Self::get). With the `alloc` feature, the missing keys are also
+    /// collected, which allocates nothing if every key is found.
+    pub fn lookup_all<'i>(
+        &self,
+        keys: impl IntoIterator<Item = TableIndex<'i>>,
+    ) -> LookupSummary<'i> {
+        let mut summary = LookupSummary::default();
+        for key in keys {
+            let value = match &key {
+                TableIndex::HashIndex(hash) => self.get(*hash),
+                TableIndex::StringIndex(name) => self.get(&**name),
+            };
+            if value.is_some() {
+                summary.hits += 1;
+            } else {
+                summary.misses += 1;
+                #[cfg(feature = "alloc")]
+                summary.missing.push(key);
+            }
+        }
+        summary
+    }
+
+    /// Check if the specified hash or resource name is present in the table,
+    /// without falling back. A hash is only looked up in the hash table and a
+    /// resource name is only looked up in the name table.
+    pub fn contains_strict<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
+        self.get_strict(needle).is_some()
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name if
+    /// present, without falling back. A hash is only looked up in the hash
+    /// table and a resource name is only looked up in the name table.
+    pub fn get_strict<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
+        fn inner(tbl: &ResTblReader, needle: TableIndex) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => tbl.find_hash_entry(hash).map(|e| e.value),
+                TableIndex::StringIndex(name) => tbl.find_name_entry(&name).map(|e| e.value),
+            }
+        }
+        inner(self, needle.into())
+    }
+
     fn parse_name_entry(&self, index: NameTableIndex) -> Result<NameEntry> {
-        let start = self.name_table_offset() + index.0 * size_of::<NameEntry>();
-        let end = start + size_of::<NameEntry>();
-        NameEntry::read(&self.data[start..end])
+        let start = self.name_table_offset() + index.0 * self.name_entry_size();
+        let end = start + self.name_entry_size();
+        NameEntry::read_with_width(&self.data[start..end], self.name_width)
     }
 
     fn find_hash_entry(&self, hash: u32) -> Option<HashEntry> {
+        self.find_hash_index(hash).map(|(_, entry)| entry)
+    }
+
+    fn find_hash_index(&self, hash: u32) -> Option<(usize, HashEntry)> {
         let mut start = 0;
         let mut end = self.header.crc_table_count as usize;
         while start < end {
             let mid = (start + end) / 2;
             let entry = self.parse_hash_entry(HashTableIndex(mid));
-            match entry.hash.cmp(&hash) {
+            match entry.hash().cmp(&hash) {
                 core::cmp::Ordering::Less => {
                     start = mid + 1;
                 }
                 core::cmp::Ordering::Greater => {
                     end = mid;
                 }
-                core::cmp::Ordering::Equal => return Some(entry),
+                core::cmp::Ordering::Equal => return Some((mid, entry)),
             }
         }
         None
     }
 
+    /// Returns the index of the first hash entry with a hash not less than
+    /// `hash`
+    fn hash_lower_bound(&self, hash: u32) -> usize {
+        let mut start = 0;
+        let mut end = self.header.crc_table_count as usize;
+        while start < end {
+            let mid = (start + end) / 2;
+            if self.parse_hash_entry(HashTableIndex(mid)).hash < hash {
+                start = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        start
+    }
+
+    fn hash_entries(&self) -> impl Iterator<Item = HashEntry> + '_ {
+        (0..self.header.crc_table_count as usize).map(|i| self.parse_hash_entry(HashTableIndex(i)))
+    }
+
+    /// Iterate the hash entries whose hashes fall within a range, in
+    /// ascending order. The start of the range is found by binary search.
+    pub fn hashes_in_range(
+        &self,
+        range: core::ops::Range<u32>,
+    ) -> impl Iterator<Item = HashEntry> + '_ {
+        (self.hash_lower_bound(range.start)..self.header.crc_table_count as usize)
+            .map(|i| self.parse_hash_entry(HashTableIndex(i)))
+            .take_while(move |entry| entry.hash < range.end)
+    }
+
+    /// Get up to `n` hash entries surrounding the position where `hash` is or
+    /// would be inserted, in ascending order. About half come from either
+    /// side, with more taken from one side when the other reaches the edge of
+    /// the table. Useful for inspecting hashes near a new or colliding
+    /// resource.
+    #[cfg(feature = "alloc")]
+    pub fn nearest_hashes(&self, hash: u32, n: usize) -> alloc::vec::Vec<HashEntry> {
+        let count = self.header.crc_table_count as usize;
+        let pos = self.hash_lower_bound(hash);
+        let end = (pos.saturating_sub(n / 2) + n).min(count);
+        let start = end.saturating_sub(n);
+        (start..end)
+            .map(|i| self.parse_hash_entry(HashTableIndex(i)))
+            .collect()
+    }
+
     fn find_name_entry(&self, name: &str) -> Option<NameEntry> {
+        self.find_name_index(name).map(|(_, entry)| entry)
+    }
+
+    fn find_name_index(&self, name: &str) -> Option<(usize, NameEntry)> {
         let mut start = 0;
         let mut end = self.header.name_table_count as usize;
         while start < end {
@@ -351,7 +839,7 @@ impl<'a> ResTblReader<'a> {
                 Some(core::cmp::Ordering::Greater) => {
                     end = mid;
                 }
-                Some(core::cmp::Ordering::Equal) => return Some(entry),
+                Some(core::cmp::Ordering::Equal) => return Some((mid, entry)),
                 _ => return None,
             }
         }
@@ -376,6 +864,157 @@ impl<'a> ResTblReader<'a> {
         inner(self, needle.into())
     }
 
+    /// Returns the byte range of the entry for the specified hash or resource
+    /// name, resolved the same way as [`get`](Self::get), for highlighting it
+    /// in a hex editor or patching it in place. The range is relative to the
+    /// start of the data the reader was constructed from, and covers the whole
+    /// entry, whose value is always its last 4 bytes.
+    pub fn entry_span<'i, I: Into<TableIndex<'i>>>(
+        &self,
+        needle: I,
+    ) -> Option<core::ops::Range<usize>> {
+        fn hash_span(tbl: &ResTblReader, hash: u32) -> Option<core::ops::Range<usize>> {
+            tbl.find_hash_index(hash).map(|(index, _)| {
+                let start = Header::FULL_SIZE + index * size_of::<HashEntry>();
+                start..start + size_of::<HashEntry>()
+            })
+        }
+        fn inner(tbl: &ResTblReader, needle: TableIndex) -> Option<core::ops::Range<usize>> {
+            match needle {
+                TableIndex::HashIndex(hash) => hash_span(tbl, hash),
+                TableIndex::StringIndex(name) => tbl
+                    .find_name_index(&name)
+                    .map(|(index, _)| {
+                        let start = tbl.name_table_offset() + index * tbl.name_entry_size();
+                        start..start + tbl.name_entry_size()
+                    })
+                    .or_else(|| hash_span(tbl, hash_name(&name))),
+            }
+        }
+        inner(self, needle.into())
+    }
+
+    /// Compare against another reader, listing every key whose value differs
+    /// along with its value in `self` and in `other` (`None` if absent).
+    ///
+    /// A resource stored as a name entry in one table and as a hash entry in
+    /// the other is treated as the same entry, unless the table storing the
+    /// name also has a separate entry for its hash.
+    #[cfg(feature = "alloc")]
+    pub fn changed_entries(
+        &self,
+        other: &ResTblReader<'_>,
+    ) -> alloc::vec::Vec<(TableIndex<'static>, Option<u32>, Option<u32>)> {
+        let mut changes = alloc::vec::Vec::new();
+        let (names, other_names) = (self.name_hash_index(), other.name_hash_index());
+        for entry in self.iter() {
+            let other_value = counterpart_value(self, other, &other_names, &entry.into());
+            let (key, value) = entry_key_value(entry);
+            if other_value != Some(value) {
+                changes.push((key, Some(value), other_value));
+            }
+        }
+        for entry in other.iter() {
+            if counterpart_value(other, self, &names, &entry.into()).is_none() {
+                let (key, value) = entry_key_value(entry);
+                changes.push((key, None, Some(value)));
+            }
+        }
+        changes
+    }
+
+    /// Iterate the keys present in both this reader and `other` whose values
+    /// differ, yielding each key with its value in `self` and in `other`.
+    /// Entries only in one table are skipped; use
+    /// [`changed_entries`](Self::changed_entries) for those as well. Name and
+    /// hash entries for the same resource are matched as in
+    /// `changed_entries`, so moving an entry between the tables is not a
+    /// change.
+    #[cfg(feature = "alloc")]
+    pub fn value_changes<'s>(
+        &'s self,
+        other: &'s ResTblReader<'_>,
+    ) -> impl Iterator<Item = (TableIndex<'static>, u32, u32)> + 's {
+        let other_names = other.name_hash_index();
+        self.iter().filter_map(move |entry| {
+            let other_value = counterpart_value(self, other, &other_names, &entry.into())?;
+            let (key, value) = entry_key_value(entry);
+            (other_value != value).then_some((key, value, other_value))
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn name_hash_index(&self) -> crate::diff::NameHashIndex {
+        crate::diff::NameHashIndex::new(self.iter_names().map(|e| (e.name, e.value)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn iter_names(&self) -> impl Iterator<Item = NameEntry> + '_ {
+        (0..self.header.name_table_count as usize)
+            .filter_map(|i| self.parse_name_entry(NameTableIndex(i)).ok())
+    }
+
+    /// Iterate the name table, borrowing each name directly from the backing
+    /// buffer instead of copying it into a [`Name`]. Entries whose names are
+    /// not valid UTF-8 are skipped.
+    pub fn iter_name_strs(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        let offset = self.name_table_offset();
+        (0..self.header.name_table_count as usize).filter_map(move |i| {
+            let start = offset + i * self.name_entry_size();
+            let entry = &self.data[start..start + self.name_entry_size()];
+            let name = &entry[..self.name_width];
+            let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+            let name = core::str::from_utf8(&name[..len]).ok()?;
+            Some((name, read_u32(entry, Some(self.name_width)).ok()?))
+        })
+    }
+
+    /// Iterate the resource names in the name table, borrowed from the backing
+    /// buffer as by [`iter_name_strs`](Self::iter_name_strs)
+    pub fn name_paths(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter_name_strs().map(|(name, _)| name)
+    }
+
+    /// Iterate every entry as a `(hash, value)` pair, hashing the names of
+    /// name entries on the fly, for fast numeric scans such as comparing two
+    /// files. Nothing is allocated. Hash entries come first, then name
+    /// entries, each in file order, so unlike
+    /// [`ResourceSizeTable::to_hash_vec`](crate::ResourceSizeTable::to_hash_vec)
+    /// the pairs are not sorted as a whole and a hash may repeat if a name
+    /// collides with a hash entry. Name entries which are not valid UTF-8 are
+    /// skipped.
+    pub fn iter_as_hashes(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.hash_entries()
+            .map(|entry| (entry.hash, entry.value))
+            .chain(
+                self.iter_name_strs()
+                    .map(|(name, value)| (hash_name(name), value)),
+            )
+    }
+
+    /// Iterate all RSTB entries like [`iter`](Self::iter), resolving each hash
+    /// entry to its resource name if the dictionary knows it. Name entries
+    /// always resolve to their own names.
+    #[cfg(feature = "alloc")]
+    pub fn iter_resolved<'s>(
+        &'s self,
+        dict: &'s crate::NameDict,
+    ) -> impl Iterator<Item = (crate::ResolvedKey<'s>, u32)> + 's {
+        (0..self.header.crc_table_count as usize)
+            .map(move |i| {
+                let entry = self.parse_hash_entry(HashTableIndex(i));
+                let key = dict.get(entry.hash).map_or(
+                    crate::ResolvedKey::Hash(entry.hash),
+                    crate::ResolvedKey::Name,
+                );
+                (key, entry.value)
+            })
+            .chain(
+                self.iter_name_strs()
+                    .map(|(name, value)| (crate::ResolvedKey::Name(name), value)),
+            )
+    }
+
     /// Iterate all RSTB entries across both the hash and name tables.
     pub fn iter(&self) -> ResTblIterator<'_> {
         ResTblIterator {
@@ -384,6 +1023,23 @@ impl<'a> ResTblReader<'a> {
         }
     }
 
+    #[inline(always)]
+    fn table_size(&self) -> usize {
+        self.name_table_offset() + self.header.name_table_count as usize * self.name_entry_size()
+    }
+
+    /// Get the width of the name field of each name entry, 160 bytes unless
+    /// the reader was made by [`with_name_width`](Self::with_name_width)
+    #[inline(always)]
+    pub fn name_width(&self) -> usize {
+        self.name_width
+    }
+
+    #[inline(always)]
+    fn name_entry_size(&self) -> usize {
+        self.name_width + 4
+    }
+
     #[inline(always)]
     fn name_table_offset(&self) -> usize {
         Header::FULL_SIZE + self.header.crc_table_count as usize * size_of::<HashEntry>()
@@ -395,41 +1051,250 @@ impl<'a> ResTblReader<'a> {
     }
 }
 
+/// Builder for a [`ResTblReader`] with optional validation. The default
+/// options match [`ResTblReader::new`], which checks only the header and the
+/// size of the data, in constant time. Each extra check is noted with its
+/// cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReaderBuilder {
+    validate_sorted: bool,
+    validate_utf8: bool,
+    max_entries: Option<usize>,
+    version_check: bool,
+}
+
+impl Default for ReaderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReaderBuilder {
+    /// Construct a builder with the default options of [`ResTblReader::new`]
+    pub const fn new() -> Self {
+        Self {
+            validate_sorted: false,
+            validate_utf8: false,
+            max_entries: None,
+            version_check: true,
+        }
+    }
+
+    /// Check that both tables are sorted, as by
+    /// [`ResTblReader::verify_sorted`]. Reads every entry once. Off by
+    /// default.
+    pub const fn validate_sorted(mut self, validate: bool) -> Self {
+        self.validate_sorted = validate;
+        self
+    }
+
+    /// Check that every name is valid UTF-8, as by
+    /// [`ResTblReader::validate_names`]. Reads every name entry once. Off by
+    /// default.
+    pub const fn validate_utf8(mut self, validate: bool) -> Self {
+        self.validate_utf8 = validate;
+        self
+    }
+
+    /// Reject a header claiming more than `max` entries with
+    /// [`Error::TooManyEntries`], as by [`ResTblReader::new_with_limits`].
+    /// Checked before anything else is read, in constant time. Unlimited by
+    /// default.
+    pub const fn max_entries(mut self, max: usize) -> Self {
+        self.max_entries = Some(max);
+        self
+    }
+
+    /// Reject headers other than version 1 with 160-byte names with
+    /// [`Error::UnsupportedHeader`]. When disabled, any header is accepted
+    /// and names are read with the header's string block size, as by
+    /// [`ResTblReader::with_name_width`]. Costs nothing. On by default.
+    pub const fn version_check(mut self, check: bool) -> Self {
+        self.version_check = check;
+        self
+    }
+
+    /// Construct a reader over the data, running the selected checks
+    pub fn build<'a, D: Into<Buffer<'a>>>(&self, data: D) -> Result<ResTblReader<'a>> {
+        let data = data.into();
+        let (header, name_width) = if self.version_check {
+            (Header::read(&data)?, size_of::<Name>())
+        } else {
+            let header = Header::read_any_layout(&data)?;
+            if header.string_block_size == 0 {
+                return Err(Error::UnsupportedHeader(header.version, 0));
+            }
+            (header, header.string_block_size as usize)
+        };
+        if let Some(max) = self.max_entries {
+            let count =
+                (header.crc_table_count as usize).saturating_add(header.name_table_count as usize);
+            if count > max {
+                return Err(Error::TooManyEntries(count, max));
+            }
+        }
+        let reader = ResTblReader::with_header(data, header, name_width)?;
+        if self.validate_sorted {
+            reader.verify_sorted()?;
+        }
+        if self.validate_utf8 {
+            reader.validate_names()?;
+        }
+        Ok(reader)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EntryLookup for ResTblReader<'_> {
+    fn hash_value(&self, hash: u32) -> Option<u32> {
+        self.find_hash_entry(hash).map(|e| e.value)
+    }
+
+    fn name_value(&self, name: &Name) -> Option<u32> {
+        self.find_name_entry(name).map(|e| e.value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn entry_key_value(entry: TableEntry) -> (TableIndex<'static>, u32) {
+    match entry {
+        TableEntry::Hash(entry) => (TableIndex::HashIndex(entry.hash), entry.value),
+        TableEntry::Name(entry) => (entry.name.into(), entry.value),
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl super::ResourceSizeTable {
-    /// Parse an owned table from binary form
+    /// Parse an owned table from binary form. If the data contains more than
+    /// one entry for the same key, the last one is kept; use
+    /// [`from_binary_strict`](Self::from_binary_strict) to reject such data.
     pub fn from_binary(data: impl AsRef<[u8]>) -> Result<Self> {
-        fn inner(data: &[u8]) -> Result<super::ResourceSizeTable> {
-            let parser = ResTblReader::new(data)?;
-            let mut crc_table = alloc::collections::BTreeMap::new();
-            let mut name_table = alloc::collections::BTreeMap::new();
-            for entry in parser.iter() {
-                match entry {
-                    TableEntry::Hash(entry) => crc_table.insert(entry.hash(), entry.value()),
-                    TableEntry::Name(entry) => name_table.insert(entry.name(), entry.value()),
-                };
+        Self::from_binary_impl(data.as_ref(), false)
+    }
+
+    /// Parse an owned table from binary form, failing with
+    /// [`Error::DuplicateHash`] or [`Error::DuplicateName`] if the data
+    /// contains more than one entry for the same key. Such duplicates are never
+    /// written by a correct serializer, so they indicate a corrupted file.
+    pub fn from_binary_strict(data: impl AsRef<[u8]>) -> Result<Self> {
+        Self::from_binary_impl(data.as_ref(), true)
+    }
+
+    /// Parse an owned table from the start of a larger buffer, returning it
+    /// along with the bytes following the table. The table ends exactly where
+    /// its header's entry counts say it does, as validated by
+    /// [`ResTblReader::new`].
+    pub fn from_binary_with_rest(data: &[u8]) -> Result<(Self, &[u8])> {
+        let size = ResTblReader::new(data)?.table_size();
+        let (table, rest) = data.split_at(size);
+        Ok((Self::from_binary_impl(table, false)?, rest))
+    }
+
+    fn from_binary_impl(data: &[u8], strict: bool) -> Result<Self> {
+        let parser = ResTblReader::new(data)?;
+        let mut crc_table = alloc::collections::BTreeMap::new();
+        let mut name_table = alloc::collections::BTreeMap::new();
+        for entry in parser.iter() {
+            match entry {
+                TableEntry::Hash(entry) => {
+                    if crc_table.insert(entry.hash(), entry.value()).is_some() && strict {
+                        return Err(Error::DuplicateHash(entry.hash()));
+                    }
+                }
+                TableEntry::Name(entry) => {
+                    if name_table.insert(entry.name(), entry.value()).is_some() && strict {
+                        return Err(Error::DuplicateName(entry.name().as_str().into()));
+                    }
+                }
             }
-            Ok(super::ResourceSizeTable {
-                crc_table,
-                name_table,
-            })
         }
-        inner(data.as_ref())
+        debug!(
+            "Parsed RSTB with {} hash entries and {} name entries",
+            crc_table.len(),
+            name_table.len()
+        );
+        Ok(super::ResourceSizeTable {
+            crc_table,
+            name_table,
+        })
+    }
+
+    /// Serialize hash and name entries straight from slices, skipping the
+    /// maps of an owned table entirely. Each slice must be sorted in strictly
+    /// ascending order of key, or this fails with [`Error::Unsorted`].
+    pub fn serialize_from_sorted(
+        crc: &[(u32, u32)],
+        names: &[(Name, u32)],
+    ) -> Result<alloc::vec::Vec<u8>> {
+        let mut builder = TableBuilder::with_capacity(crc.len(), names.len());
+        for (hash, value) in crc {
+            builder.push_hash(*hash, *value)?;
+        }
+        for (name, value) in names {
+            builder.push_name(name.as_str(), *value)?;
+        }
+        Ok(builder.finish())
+    }
+
+    /// Get the size in bytes of the table in its binary format
+    #[inline(always)]
+    pub fn binary_size(&self) -> usize {
+        Header::FULL_SIZE
+            + size_of::<HashEntry>() * self.crc_table.len()
+            + size_of::<NameEntry>() * self.name_table.len()
     }
 
     /// Write the table in its binary format to bytes.
     pub fn to_binary(&self) -> alloc::vec::Vec<u8> {
-        let size = Header::FULL_SIZE
-            + size_of::<HashEntry>() * self.crc_table.len()
-            + size_of::<NameEntry>() * self.name_table.len();
-        let mut buffer = alloc::vec![0u8; size];
-        Header {
-            version: 1,
-            string_block_size: size_of::<Name>() as u32,
-            crc_table_count: self.crc_table.len() as u32,
-            name_table_count: self.name_table.len() as u32,
-        }
-        .write(&mut buffer);
+        let mut buffer = alloc::vec![0u8; self.binary_size()];
+        self.write_binary_unchecked(&mut buffer);
+        buffer
+    }
+
+    /// Write the table in its binary format into an existing `Vec`, replacing
+    /// its contents. The `Vec` is resized to exactly
+    /// [`binary_size`](Self::binary_size), so when serializing many tables
+    /// its allocation can be reused.
+    pub fn to_binary_reuse(&self, buffer: &mut alloc::vec::Vec<u8>) {
+        buffer.clear();
+        buffer.resize(self.binary_size(), 0);
+        self.write_binary_unchecked(buffer);
+    }
+
+    /// Write the table in its binary format to bytes, filling the hash and
+    /// name regions concurrently with rayon. The output is identical to
+    /// [`to_binary`](Self::to_binary); this only pays off for very large
+    /// generated tables.
+    #[cfg(feature = "rayon")]
+    pub fn to_binary_parallel(&self) -> alloc::vec::Vec<u8> {
+        use rayon::prelude::*;
+        let hashes = self.crc_table.iter().collect::<alloc::vec::Vec<_>>();
+        let names = self.name_table.iter().collect::<alloc::vec::Vec<_>>();
+        let mut buffer = alloc::vec![0u8; self.binary_size()];
+        let (header, entries) = buffer.split_at_mut(Header::FULL_SIZE);
+        Header::with_counts(hashes.len() as u32, names.len() as u32).write(header);
+        let (hash_region, name_region) =
+            entries.split_at_mut(hashes.len() * size_of::<HashEntry>());
+        rayon::join(
+            || {
+                hash_region
+                    .par_chunks_mut(size_of::<HashEntry>())
+                    .zip(hashes.par_iter())
+                    .for_each(|(chunk, (hash, value))| HashEntry::new(**hash, **value).write(chunk))
+            },
+            || {
+                name_region
+                    .par_chunks_mut(size_of::<NameEntry>())
+                    .zip(names.par_iter())
+                    .for_each(|(chunk, (name, value))| NameEntry::new(**name, **value).write(chunk))
+            },
+        );
+        buffer
+    }
+
+    fn write_binary_unchecked(&self, buffer: &mut [u8]) {
+        Header::with_counts(self.crc_table.len() as u32, self.name_table.len() as u32)
+            .write(buffer);
         let mut pos = Header::FULL_SIZE;
         for (hash, value) in &self.crc_table {
             HashEntry {
@@ -447,13 +1312,278 @@ impl super::ResourceSizeTable {
             .write(&mut buffer[pos..]);
             pos += size_of::<NameEntry>();
         }
-        buffer
     }
-}
-
-#[cfg(test)]
-mod test {
-    use crate::test::DATA;
+
+    /// Generate Rust source declaring the serialized table as a byte array
+    /// constant, `const NAME: &[u8] = &[...];`, for embedding it in a binary
+    /// with `include!` (e.g. from a build script) instead of shipping a
+    /// separate file. Read the constant with [`ResTblReader::new`].
+    pub fn to_rust_source(&self, const_name: &str) -> alloc::string::String {
+        use core::fmt::Write;
+        let bytes = self.to_binary();
+        let mut source = alloc::string::String::with_capacity(bytes.len() * 6 + 64);
+        writeln!(source, "const {const_name}: &[u8] = &[")
+            .expect("Writing to a String cannot fail");
+        for line in bytes.chunks(16) {
+            source.push_str("   ");
+            for byte in line {
+                write!(source, " {byte:#04x},").expect("Writing to a String cannot fail");
+            }
+            source.push('\n');
+        }
+        source.push_str("];\n");
+        source
+    }
+
+    /// Write the table in its binary format to a writer
+    #[cfg(feature = "std")]
+    pub fn write_binary(&self, writer: impl std::io::Write) -> Result<()> {
+        self.write_binary_with_progress(writer, |_, _| ())
+    }
+
+    /// Write the table in its binary format to a writer, reporting progress as
+    /// `(bytes_written, total)`, where the total is
+    /// [`binary_size`](Self::binary_size). The callback runs once after the
+    /// header, after every 1024 entries, and once more when writing finishes
+    /// unless the last entry was just reported.
+    #[cfg(feature = "std")]
+    pub fn write_binary_with_progress(
+        &self,
+        mut writer: impl std::io::Write,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<()> {
+        const PROGRESS_INTERVAL: usize = 1024;
+        let total = self.binary_size();
+        let mut header = [0u8; Header::FULL_SIZE];
+        Header::with_counts(self.crc_table.len() as u32, self.name_table.len() as u32)
+            .write(&mut header);
+        writer.write_all(&header)?;
+        let mut written = Header::FULL_SIZE;
+        on_progress(written, total);
+        let mut buffer = [0u8; size_of::<NameEntry>()];
+        let mut count = 0;
+        for (hash, value) in &self.crc_table {
+            HashEntry::new(*hash, *value).write(&mut buffer);
+            writer.write_all(&buffer[..size_of::<HashEntry>()])?;
+            written += size_of::<HashEntry>();
+            count += 1;
+            if count % PROGRESS_INTERVAL == 0 {
+                on_progress(written, total);
+            }
+        }
+        for (name, value) in &self.name_table {
+            NameEntry::new(*name, *value).write(&mut buffer);
+            writer.write_all(&buffer)?;
+            written += size_of::<NameEntry>();
+            count += 1;
+            if count % PROGRESS_INTERVAL == 0 {
+                on_progress(written, total);
+            }
+        }
+        if count % PROGRESS_INTERVAL != 0 {
+            on_progress(written, total);
+        }
+        Ok(())
+    }
+
+    /// Read and parse a table from a binary file
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_binary(std::fs::read(path)?)
+    }
+
+    /// Write the table in its binary format to a file, replacing it if it
+    /// exists.
+    ///
+    /// The data is first written to a temporary file beside the destination,
+    /// which is then renamed over it, so an interrupted save leaves the
+    /// original file intact rather than truncated. The temporary file is
+    /// removed if writing fails.
+    #[cfg(feature = "std")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        crate::util::write_atomic(path.as_ref(), |file| self.write_binary(file))
+    }
+
+    /// Write the table in its binary format to bytes, zero padded to the next
+    /// multiple of `align`. Readers ignore the padding, so the output parses
+    /// the same as [`to_binary`](Self::to_binary).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is 0.
+    pub fn to_binary_aligned(&self, align: usize) -> alloc::vec::Vec<u8> {
+        let mut buffer = self.to_binary();
+        buffer.resize(buffer.len().next_multiple_of(align), 0);
+        buffer
+    }
+
+    /// Check if this table would serialize to exactly the same bytes as
+    /// another, without serializing either. Every table is written with the
+    /// same header layout and its entries in sorted order, and names are
+    /// always zero padded, so this holds exactly when both tables have the
+    /// same entries.
+    pub fn binary_eq(&self, other: &crate::ResourceSizeTable) -> bool {
+        self.crc_table.len() == other.crc_table.len()
+            && self.name_table.len() == other.name_table.len()
+            && self.crc_table.iter().eq(other.crc_table.iter())
+            && self.name_table.iter().eq(other.name_table.iter())
+    }
+}
+
+/// Append-only writer which serializes already-sorted entries straight into a
+/// binary RSTB without building an owned table first. All hash entries must be
+/// pushed before any name entries, and each region must be pushed in strictly
+/// ascending order.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct TableBuilder {
+    buffer: alloc::vec::Vec<u8>,
+    crc_table_count: u32,
+    name_table_count: u32,
+    last_hash: Option<u32>,
+    last_name: Option<Name>,
+}
+
+#[cfg(feature = "alloc")]
+impl Default for TableBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TableBuilder {
+    /// Construct an empty builder
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Construct an empty builder with space reserved for the given number of
+    /// hash and name entries
+    pub fn with_capacity(hash_count: usize, name_count: usize) -> Self {
+        let mut buffer = alloc::vec::Vec::with_capacity(
+            Header::FULL_SIZE
+                + hash_count * size_of::<HashEntry>()
+                + name_count * size_of::<NameEntry>(),
+        );
+        buffer.resize(Header::FULL_SIZE, 0);
+        Self {
+            buffer,
+            crc_table_count: 0,
+            name_table_count: 0,
+            last_hash: None,
+            last_name: None,
+        }
+    }
+
+    /// Append a hash entry. Fails if the hash is not greater than the last
+    /// hash pushed or if any name entries have already been pushed.
+    pub fn push_hash(&mut self, hash: u32, value: u32) -> Result<()> {
+        if self.name_table_count > 0 || self.last_hash.is_some_and(|last| last >= hash) {
+            return Err(Error::Unsorted);
+        }
+        let pos = self.buffer.len();
+        self.buffer.resize(pos + size_of::<HashEntry>(), 0);
+        HashEntry { hash, value }.write(&mut self.buffer[pos..]);
+        self.crc_table_count += 1;
+        self.last_hash = Some(hash);
+        Ok(())
+    }
+
+    /// Append a name entry. The name is cleaned up as by [`Name::new`], so
+    /// this fails if it is too long for the name field, or if it does not sort
+    /// after the last name pushed.
+    pub fn push_name(&mut self, name: &str, value: u32) -> Result<()> {
+        let name = Name::new(name)?;
+        if self.last_name.is_some_and(|last| last >= name) {
+            return Err(Error::Unsorted);
+        }
+        let pos = self.buffer.len();
+        self.buffer.resize(pos + size_of::<NameEntry>(), 0);
+        NameEntry { name, value }.write(&mut self.buffer[pos..]);
+        self.name_table_count += 1;
+        self.last_name = Some(name);
+        Ok(())
+    }
+
+    /// Write the header and return the finished binary table
+    pub fn finish(mut self) -> alloc::vec::Vec<u8> {
+        Header::with_counts(self.crc_table_count, self.name_table_count).write(&mut self.buffer);
+        self.buffer
+    }
+}
+
+/// Parser which reads an RSTB from a [`std::io::Read`] one entry at a time,
+/// never holding more than a single entry in memory. Unlike [`ResTblReader`],
+/// this supports only a single forward pass.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StreamParser<R> {
+    reader: R,
+    header: Header,
+    index: usize,
+    failed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> StreamParser<R> {
+    /// Read and validate the header, leaving the reader positioned at the
+    /// first entry
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut header = [0u8; Header::FULL_SIZE];
+        reader.read_exact(&mut header)?;
+        Ok(Self {
+            header: Header::read(&header)?,
+            reader,
+            index: 0,
+            failed: false,
+        })
+    }
+
+    #[inline(always)]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Get the underlying reader back, positioned after the last entry read
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_entry(&mut self) -> Result<TableEntry> {
+        if self.index < self.header.crc_table_count as usize {
+            let mut data = [0u8; size_of::<HashEntry>()];
+            self.reader.read_exact(&mut data)?;
+            Ok(TableEntry::Hash(HashEntry::read(&data)?))
+        } else {
+            let mut data = [0u8; size_of::<NameEntry>()];
+            self.reader.read_exact(&mut data)?;
+            Ok(TableEntry::Name(NameEntry::read(&data)?))
+        }
+    }
+}
+
+/// Yields each entry in file order, hash entries first. After an error, such
+/// as the data ending early, iteration stops.
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for StreamParser<R> {
+    type Item = Result<TableEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = self.header.crc_table_count as usize + self.header.name_table_count as usize;
+        if self.failed || self.index >= count {
+            return None;
+        }
+        let entry = self.read_entry();
+        self.index += 1;
+        self.failed = entry.is_err();
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::DATA;
 
     #[test]
     fn parse() {
@@ -481,22 +1611,841 @@ mod test {
             .unwrap();
         dbg!(entry);
         assert!(parser.get("Pack/Actor/Nonexistent.pack").is_none());
+        assert_eq!(
+            parser.get_or("Cooking/CookingTable.game__cooking__Table.bgyml", 0),
+            entry
+        );
+        assert_eq!(parser.get_or("Pack/Actor/Nonexistent.pack", 0), 0);
+    }
+
+    #[test]
+    fn iter_name_strs() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let mut names = parser.iter_name_strs();
+        for entry in parser.iter() {
+            if let super::TableEntry::Name(entry) = entry {
+                assert_eq!(names.next(), Some((entry.name().as_str(), entry.value())));
+            }
+        }
+        assert_eq!(names.next(), None);
+    }
+
+    #[test]
+    fn trailing() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        assert_eq!(parser.trailing_len(), 0);
+        let mut padded = DATA.to_vec();
+        padded.extend_from_slice(&[0; 10]);
+        let parser = super::ResTblReader::new_strict(padded.as_slice()).unwrap();
+        assert_eq!(parser.trailing_len(), 10);
+        padded.push(1);
+        assert!(matches!(
+            super::ResTblReader::new_strict(padded.as_slice()),
+            Err(crate::Error::TrailingData(11))
+        ));
+    }
+
+    #[test]
+    fn sub_slice() {
+        let mut archive = vec![0xAA; 0x40];
+        archive.extend_from_slice(DATA);
+        archive.extend_from_slice(b"SARC trailing file data");
+        let parser = super::ResTblReader::new(&archive[0x40..]).unwrap();
+        assert_eq!(parser.trailing_len(), 23);
+        let original = super::ResTblReader::new(DATA).unwrap();
+        assert_eq!(parser.len(), original.len());
+        assert_eq!(
+            parser.get("Bake/Scene/MainField_G_26_43.bkres"),
+            Some(31880)
+        );
+        assert!(parser.get("Pack/Actor/Nonexistent.pack").is_none());
+        assert!(parser.iter().eq(original.iter()));
+    }
+
+    #[test]
+    fn hashes_in_range() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let range = 0x1000_0000..0x2000_0000;
+        let expected = parser
+            .iter()
+            .filter_map(|entry| match entry {
+                super::TableEntry::Hash(entry) if range.contains(&entry.hash()) => Some(entry),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty());
+        assert!(parser.hashes_in_range(range).eq(expected));
+        assert_eq!(parser.hashes_in_range(5..5).count(), 0);
+        assert_eq!(
+            parser.hashes_in_range(0..u32::MAX).count(),
+            parser.header().crc_table_count() as usize
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn nearest_hashes() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let hashes = parser.hashes_in_range(0..u32::MAX).collect::<Vec<_>>();
+        let mid = hashes[100].hash();
+        assert_eq!(parser.nearest_hashes(mid, 4), hashes[98..102]);
+        assert_eq!(parser.nearest_hashes(mid + 1, 3), hashes[100..103]);
+        assert_eq!(parser.nearest_hashes(0, 3), hashes[..3]);
+        assert_eq!(
+            parser.nearest_hashes(u32::MAX, 3),
+            hashes[hashes.len() - 3..]
+        );
+        assert!(parser.nearest_hashes(mid, 0).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn verify_counts() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        parser.verify_counts().unwrap();
+
+        let mut builder = super::TableBuilder::new();
+        builder.push_hash(1, 10).unwrap();
+        builder.push_name("A.bgyml", 20).unwrap();
+        builder.push_name("B.bgyml", 30).unwrap();
+        let mut data = builder.finish();
+        // Corrupt the first name so iteration stops early
+        data[super::Header::FULL_SIZE + 8] = 0xFF;
+        let parser = super::ResTblReader::new(data.as_slice()).unwrap();
+        assert!(matches!(
+            parser.verify_counts(),
+            Err(crate::Error::EntryCountMismatch(3, 1))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn validate_names() {
+        super::ResTblReader::new(DATA)
+            .unwrap()
+            .validate_names()
+            .unwrap();
+        let mut builder = super::TableBuilder::new();
+        builder.push_name("A.bgyml", 20).unwrap();
+        builder.push_name("B.bgyml", 30).unwrap();
+        let mut data = builder.finish();
+        data[super::Header::FULL_SIZE + size_of::<super::NameEntry>() + 1] = 0xFF;
+        let parser = super::ResTblReader::new(data.as_slice()).unwrap();
+        assert!(matches!(
+            parser.validate_names(),
+            Err(crate::Error::InvalidName(1, _))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_owned() {
+        let parser = {
+            let data = DATA.to_vec();
+            super::ResTblReader::new(data.as_slice())
+                .unwrap()
+                .into_owned()
+        };
+        assert_eq!(parser.len(), super::ResTblReader::new(DATA).unwrap().len());
+        assert_eq!(
+            parser.get("Bake/Scene/MainField_G_26_43.bkres"),
+            Some(31880)
+        );
+        let owned = super::ResTblReader::new(DATA.to_vec())
+            .unwrap()
+            .into_owned();
+        assert!(owned.iter().eq(parser.iter()));
+    }
+
+    #[test]
+    fn lookup_strict() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let name = "Bake/Scene/MainField_U_30_50.bkres";
+        assert_eq!(parser.get_strict(name), Some(64416));
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        assert!(parser.get(hashed).is_some());
+        assert!(!parser.contains_strict(hashed));
+        assert!(parser.contains_strict(crate::util::hash_name(hashed)));
     }
 
     #[cfg(feature = "alloc")]
     #[test]
     fn serialize() {
+        crate::testutil::assert_roundtrip(DATA);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_with_progress() {
         let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
-        let bytes = table.to_binary();
-        let table2 = crate::ResourceSizeTable::from_binary(&bytes).unwrap();
-        assert_eq!(table.crc_table.len(), table2.crc_table.len());
-        for (entry1, entry2) in table.crc_table.iter().zip(table2.crc_table.iter()) {
-            assert_eq!(entry1, entry2);
+        let mut buffer = Vec::new();
+        let mut reports = Vec::new();
+        table
+            .write_binary_with_progress(&mut buffer, |written, total| {
+                reports.push((written, total))
+            })
+            .unwrap();
+        assert_eq!(buffer, DATA);
+        assert_eq!(reports.len(), 1 + table.len().div_ceil(1024));
+        assert_eq!(
+            reports.first(),
+            Some(&(super::Header::FULL_SIZE, DATA.len()))
+        );
+        assert_eq!(reports.last(), Some(&(DATA.len(), DATA.len())));
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let table = crate::ResourceSizeTable::from(
+            (0..2048)
+                .map(|i| (i, i))
+                .collect::<alloc::collections::BTreeMap<u32, u32>>(),
+        );
+        let mut calls = 0;
+        table
+            .write_binary_with_progress(std::io::sink(), |_, _| calls += 1)
+            .unwrap();
+        assert_eq!(calls, 3);
+        calls = 0;
+        crate::ResourceSizeTable::new()
+            .write_binary_with_progress(std::io::sink(), |_, _| calls += 1)
+            .unwrap();
+        assert_eq!(calls, 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn save_load() {
+        let dir = crate::test::temp_dir("save_load");
+        let path = dir.join("Test.rsizetable");
+        std::fs::write(&path, b"old").unwrap();
+        let mut table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        table.set("Test/New.bgyml", 1);
+        table.save(&path).unwrap();
+        let loaded = crate::ResourceSizeTable::load(&path).unwrap();
+        let missing = crate::ResourceSizeTable::load(dir.join("Missing.rsizetable"));
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        let bad_save = table.save(dir.join("Missing/Test.rsizetable"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded, table);
+        assert!(matches!(missing, Err(crate::Error::IoError(_))));
+        assert_eq!(files, 1);
+        assert!(bad_save.is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn rust_source() {
+        let mut table = crate::ResourceSizeTable::new();
+        table.set(1, 2);
+        let source = table.to_rust_source("BASELINE");
+        assert!(source.starts_with("const BASELINE: &[u8] = &[\n    0x52, 0x45,"));
+        assert!(source.ends_with("0x00,\n];\n"));
+        let bytes = source
+            .lines()
+            .skip(1)
+            .flat_map(|line| line.split(',').map(str::trim))
+            .filter_map(|byte| byte.strip_prefix("0x"))
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, table.to_binary());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stream_parser() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let stream = super::StreamParser::new(DATA).unwrap();
+        assert_eq!(
+            stream.header().crc_table_count(),
+            parser.header().crc_table_count()
+        );
+        assert!(stream.map(|entry| entry.unwrap()).eq(parser.iter()));
+        let mut truncated = super::StreamParser::new(&DATA[..DATA.len() - 1]).unwrap();
+        assert!(truncated.by_ref().take(parser.len() - 1).all(|e| e.is_ok()));
+        assert!(matches!(
+            truncated.next(),
+            Some(Err(crate::Error::IoError(_)))
+        ));
+        assert!(truncated.next().is_none());
+        assert!(super::StreamParser::new(&DATA[..4]).is_err());
+    }
+
+    #[test]
+    fn file_order() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let mut entries = parser.iter().collect::<Vec<_>>();
+        assert!(entries.is_sorted_by(|a, b| a.cmp_file_order(b).is_le()));
+        entries.reverse();
+        entries.sort_by(super::TableEntry::cmp_file_order);
+        assert!(entries.into_iter().eq(parser.iter()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn iter_as_hashes() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let pairs = parser.iter_as_hashes().collect::<Vec<_>>();
+        assert_eq!(pairs.len(), parser.len());
+        let crc_count = parser.header().crc_table_count() as usize;
+        assert!(pairs[..crc_count].windows(2).all(|w| w[0].0 < w[1].0));
+        let mut sorted = pairs;
+        sorted.sort_unstable();
+        sorted.dedup_by_key(|(hash, _)| *hash);
+        let flat = crate::ResourceSizeTable::from_parser(&parser).to_hash_vec();
+        assert!(sorted
+            .iter()
+            .map(|(hash, _)| hash)
+            .eq(flat.iter().map(|(hash, _)| hash)));
+        let name = "Bake/Scene/MainField_G_26_43.bkres";
+        assert!(parser
+            .iter_as_hashes()
+            .any(|pair| pair == (crate::util::hash_name(name), 31880)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn value_order() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let mut heap = parser.iter().collect::<alloc::collections::BinaryHeap<_>>();
+        let max = parser.iter().map(|entry| entry.value()).max().unwrap();
+        let largest = heap.pop().unwrap();
+        assert_eq!(largest.value(), max);
+        assert!(heap.pop().unwrap().value() <= max);
+        let hash = super::TableEntry::Hash(super::HashEntry::new(1, 5));
+        let name = super::TableEntry::Name(super::NameEntry::new("A.bgyml".into(), 5));
+        let smaller = super::TableEntry::Name(super::NameEntry::new("A.bgyml".into(), 4));
+        assert!(hash < name);
+        assert!(smaller < hash);
+        let mut heap = alloc::collections::BinaryHeap::from([smaller, name, hash]);
+        assert_eq!(heap.pop(), Some(name));
+        assert_eq!(heap.pop(), Some(hash));
+        assert_eq!(heap.pop(), Some(smaller));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn serialize_from_sorted() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let crc = table
+            .crc_table
+            .iter()
+            .map(|(hash, value)| (*hash, *value))
+            .collect::<Vec<_>>();
+        let names = table
+            .name_table
+            .iter()
+            .map(|(name, value)| (*name, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            crate::ResourceSizeTable::serialize_from_sorted(&crc, &names).unwrap(),
+            DATA
+        );
+        assert!(matches!(
+            crate::ResourceSizeTable::serialize_from_sorted(&[(2, 0), (1, 0)], &[]),
+            Err(crate::Error::Unsorted)
+        ));
+        let mut reversed = names.clone();
+        reversed.reverse();
+        assert!(matches!(
+            crate::ResourceSizeTable::serialize_from_sorted(&crc, &reversed),
+            Err(crate::Error::Unsorted)
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn iter_tables() {
+        let mut small = crate::ResourceSizeTable::new();
+        small.set(1, 2);
+        small.set("Test/Name.bgyml", 3);
+        let mut data = DATA.to_vec();
+        data.extend(small.to_binary());
+        data.extend(crate::ResourceSizeTable::new().to_binary());
+        let tables = super::ResTblReader::iter_tables(&data)
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(tables.len(), 3);
+        assert_eq!(
+            tables[0].len(),
+            super::ResTblReader::new(DATA).unwrap().len()
+        );
+        assert_eq!(tables[1].get("Test/Name.bgyml"), Some(3));
+        assert!(tables[2].is_empty());
+        data.extend(b"RESTBL");
+        let mut tables = super::ResTblReader::iter_tables(&data).skip(3);
+        assert!(matches!(
+            tables.next(),
+            Some(Err(crate::Error::InsufficientData(6, _)))
+        ));
+        assert!(tables.next().is_none());
+    }
+
+    #[test]
+    fn lookup_all() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let summary = parser.lookup_all(parser.iter_name_strs().map(|(name, _)| name.into()));
+        assert_eq!(summary.hits, parser.header().name_table_count() as usize);
+        assert!(summary.all_found());
+        let keys = [
+            "Bake/Scene/MainField_G_26_43.bkres".into(),
+            "Pack/Actor/Nonexistent.pack".into(),
+            crate::TableIndex::HashIndex(0),
+        ];
+        let summary = parser.lookup_all(keys.clone());
+        assert_eq!((summary.hits, summary.misses), (1, 2));
+        assert!(!summary.all_found());
+        #[cfg(feature = "alloc")]
+        assert_eq!(summary.missing, keys[1..]);
+    }
+
+    #[test]
+    fn status() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        assert_eq!(
+            parser.status("Bake/Scene/MainField_G_26_43.bkres"),
+            crate::EntryStatus::Present(31880)
+        );
+        assert_eq!(
+            parser.status("Pack/Actor/Nonexistent.pack"),
+            crate::EntryStatus::Absent
+        );
+    }
+
+    #[test]
+    fn name_width() {
+        fn table(width: usize, names: &[&str]) -> ([u8; 0x400], usize) {
+            let mut data = [0u8; 0x400];
+            super::Header::with_counts(1, names.len() as u32).write(&mut data);
+            data[10..14].copy_from_slice(&(width as u32).to_le_bytes());
+            let mut pos = super::Header::FULL_SIZE;
+            super::HashEntry::new(7, 70).write(&mut data[pos..]);
+            pos += 8;
+            for (i, name) in names.iter().enumerate() {
+                data[pos..pos + name.len()].copy_from_slice(name.as_bytes());
+                data[pos + width..pos + width + 4].copy_from_slice(&(i as u32).to_le_bytes());
+                pos += width + 4;
+            }
+            (data, pos)
         }
-        assert_eq!(table.name_table.len(), table2.name_table.len());
-        for (entry1, entry2) in table.name_table.iter().zip(table2.name_table.iter()) {
-            assert_eq!(entry1, entry2);
+
+        let (data, len) = table(0x40, &["A.bgyml", "B.bgyml"]);
+        let data = &data[..len];
+        assert!(matches!(
+            super::ResTblReader::new(data),
+            Err(crate::Error::UnsupportedHeader(1, 0x40))
+        ));
+        let parser = super::ResTblReader::with_name_width(data, None).unwrap();
+        assert_eq!(parser.name_width(), 0x40);
+        assert_eq!(parser.iter().count(), 3);
+        assert_eq!(parser.get(7), Some(70));
+        assert_eq!(parser.get("A.bgyml"), Some(0));
+        assert_eq!(parser.get("B.bgyml"), Some(1));
+        assert!(parser.iter_name_strs().eq([("A.bgyml", 0), ("B.bgyml", 1)]));
+        assert!(parser.verify_counts().is_ok());
+        assert!(super::ResTblReader::with_name_width(&data[..len - 1], None).is_err());
+        assert!(super::ResTblReader::with_name_width(data, Some(0)).is_err());
+
+        let long = "a".repeat(170);
+        let (data, len) = table(0xC0, &["A.bgyml", "B.bgyml", &long]);
+        let parser = super::ResTblReader::with_name_width(&data[..len], Some(0xC0)).unwrap();
+        assert_eq!(parser.get("A.bgyml"), Some(0));
+        assert_eq!(parser.iter().count(), 3);
+        assert!(parser.verify_counts().is_err());
+
+        let parser = super::ResTblReader::with_name_width(DATA, Some(160)).unwrap();
+        assert!(parser
+            .iter()
+            .eq(super::ResTblReader::new(DATA).unwrap().iter()));
+    }
+
+    #[test]
+    fn unsupported_header() {
+        let mut data = DATA[..super::Header::FULL_SIZE].to_vec();
+        data[6..10].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            super::Header::parse(&data),
+            Err(crate::Error::UnsupportedHeader(2, 0xA0))
+        ));
+        data[6..10].copy_from_slice(&1u32.to_le_bytes());
+        data[10..14].copy_from_slice(&0x100u32.to_le_bytes());
+        assert!(matches!(
+            super::Header::parse(&data),
+            Err(crate::Error::UnsupportedHeader(1, 0x100))
+        ));
+    }
+
+    #[test]
+    fn entry_span() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        let span = parser.entry_span(hashed).unwrap();
+        assert_eq!(span.len(), 8);
+        assert_eq!(
+            &DATA[span.clone()][..4],
+            crate::util::hash_name(hashed).to_le_bytes()
+        );
+        assert_eq!(&DATA[span.end - 4..span.end], 31880u32.to_le_bytes());
+        assert_eq!(
+            parser.entry_span(crate::util::hash_name(hashed)),
+            Some(span)
+        );
+        let (name, value) = parser.iter_name_strs().last().unwrap();
+        let span = parser.entry_span(name).unwrap();
+        assert_eq!(span.end, DATA.len());
+        assert_eq!(span.len(), 0xA4);
+        assert_eq!(&DATA[span.start..span.start + name.len()], name.as_bytes());
+        assert_eq!(&DATA[span.end - 4..span.end], value.to_le_bytes());
+        assert_eq!(parser.entry_span("Pack/Actor/Nonexistent.pack"), None);
+    }
+
+    #[test]
+    fn short_or_compressed() {
+        assert!(matches!(
+            super::ResTblReader::new(&[][..]),
+            Err(crate::Error::InsufficientData(0, _))
+        ));
+        assert!(matches!(
+            super::ResTblReader::new(&DATA[..4]),
+            Err(crate::Error::InsufficientData(4, _))
+        ));
+        // A zstd frame header, as at the start of a .zs file
+        let compressed = [0x28, 0xb5, 0x2f, 0xfd, 0x60, 0x44, 0x2e];
+        assert!(matches!(
+            super::ResTblReader::new(&compressed[..]),
+            Err(crate::Error::Compressed)
+        ));
+        assert!(matches!(
+            super::ReaderBuilder::new()
+                .version_check(false)
+                .build(&compressed[..4]),
+            Err(crate::Error::Compressed)
+        ));
+    }
+
+    #[test]
+    fn huge_counts() {
+        let mut data = [0u8; super::Header::FULL_SIZE];
+        super::Header::with_counts(u32::MAX, u32::MAX).write(&mut data);
+        assert!(matches!(
+            super::ResTblReader::new(data.as_slice()),
+            Err(crate::Error::InvalidTableSize(len, _)) if len == data.len()
+        ));
+        super::Header::with_counts(0, u32::MAX).write(&mut data);
+        assert!(matches!(
+            super::ResTblReader::new(data.as_slice()),
+            Err(crate::Error::InvalidTableSize(..))
+        ));
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn hash_entries_slice() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let entries = parser.hash_entries_slice();
+        assert_eq!(entries.len(), parser.header().crc_table_count() as usize);
+        assert!(entries
+            .windows(2)
+            .all(|pair| pair[0].hash() < pair[1].hash()));
+        let hash = crate::util::hash_name("Bake/Scene/MainField_G_26_43.bkres");
+        let index = entries
+            .binary_search_by_key(&hash, super::HashEntry::hash)
+            .unwrap();
+        assert_eq!(entries[index].value(), 31880);
+        assert!(parser
+            .iter()
+            .take(entries.len())
+            .eq(entries.iter().copied().map(super::TableEntry::Hash)));
+        let mut empty = [0u8; super::Header::FULL_SIZE];
+        super::Header::with_counts(0, 0).write(&mut empty);
+        assert!(super::ResTblReader::new(empty.as_slice())
+            .unwrap()
+            .hash_entries_slice()
+            .is_empty());
+    }
+
+    #[test]
+    fn reader_builder() {
+        use super::ReaderBuilder;
+        let parser = ReaderBuilder::default().build(DATA).unwrap();
+        assert!(parser
+            .iter()
+            .eq(super::ResTblReader::new(DATA).unwrap().iter()));
+        let strict = ReaderBuilder::new()
+            .validate_sorted(true)
+            .validate_utf8(true)
+            .max_entries(parser.len());
+        assert!(strict.build(DATA).is_ok());
+        assert!(matches!(
+            strict.max_entries(10).build(DATA),
+            Err(crate::Error::TooManyEntries(_, 10))
+        ));
+
+        let mut data = DATA.to_vec();
+        let first = super::Header::FULL_SIZE;
+        let (a, b) = data[first..first + 16].split_at_mut(8);
+        a.swap_with_slice(b);
+        assert!(ReaderBuilder::new().build(data.as_slice()).is_ok());
+        assert!(matches!(
+            strict.build(data.as_slice()),
+            Err(crate::Error::Unsorted)
+        ));
+
+        let mut data = DATA.to_vec();
+        let name_offset = super::Header::FULL_SIZE
+            + parser.header().crc_table_count() as usize * core::mem::size_of::<super::HashEntry>();
+        data[name_offset] = 0xFF;
+        assert!(ReaderBuilder::new().build(data.as_slice()).is_ok());
+        assert!(matches!(
+            ReaderBuilder::new()
+                .validate_utf8(true)
+                .build(data.as_slice()),
+            Err(crate::Error::InvalidName(0, _))
+        ));
+
+        let mut data = DATA.to_vec();
+        data[6..10].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            ReaderBuilder::new().build(data.as_slice()),
+            Err(crate::Error::UnsupportedHeader(2, 0xA0))
+        ));
+        let parser = ReaderBuilder::new()
+            .version_check(false)
+            .build(data.as_slice())
+            .unwrap();
+        assert_eq!(parser.header().version(), 2);
+        assert_eq!(
+            parser.get("Bake/Scene/MainField_G_26_43.bkres"),
+            Some(31880)
+        );
+    }
+
+    #[test]
+    fn entry_limits() {
+        let mut data = [0u8; super::Header::FULL_SIZE];
+        super::Header::with_counts(u32::MAX, u32::MAX).write(&mut data);
+        assert!(matches!(
+            super::ResTblReader::new_with_limits(data.as_slice(), 1_000_000),
+            Err(crate::Error::TooManyEntries(count, 1_000_000))
+                if count == (u32::MAX as usize).saturating_mul(2)
+        ));
+        let len = super::ResTblReader::new(DATA).unwrap().len();
+        assert!(super::ResTblReader::new_with_limits(DATA, len).is_ok());
+        assert!(matches!(
+            super::ResTblReader::new_with_limits(DATA, len - 1),
+            Err(crate::Error::TooManyEntries(count, _)) if count == len
+        ));
+    }
+
+    #[test]
+    fn header_parse() {
+        let header = super::Header::parse(&DATA[..super::Header::FULL_SIZE]).unwrap();
+        let reader = super::ResTblReader::new(DATA).unwrap();
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.crc_table_count(), reader.header().crc_table_count());
+        assert_eq!(
+            header.name_table_count(),
+            reader.header().name_table_count()
+        );
+        assert!(matches!(
+            super::Header::parse(b"RESTBX\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"),
+            Err(crate::Error::InvalidMagic(_))
+        ));
+        assert!(matches!(
+            super::Header::parse(&DATA[..8]),
+            Err(crate::Error::InsufficientData(8, _))
+        ));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel() {
+        let mut table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(table.to_binary_parallel(), DATA);
+        table.set("Test/Extra.bgyml", 1);
+        table.name_table.insert("Test/Name.bgyml".into(), 2);
+        assert_eq!(table.to_binary_parallel(), table.to_binary());
+        assert_eq!(
+            crate::ResourceSizeTable::new().to_binary_parallel(),
+            crate::ResourceSizeTable::new().to_binary()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn value_changes() {
+        let base = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let moved = "Test/Moved.bgyml";
+        let changed = "Bake/Scene/MainField_G_26_43.bkres";
+        let mut a = base.clone();
+        a.name_table.insert(moved.into(), 5);
+        a.set(changed, 1);
+        a.set("Test/OnlyA.bgyml", 2);
+        let mut b = base;
+        b.crc_table.insert(crate::util::hash_name(moved), 5);
+        b.set("Test/OnlyB.bgyml", 3);
+        let (a, b) = (a.to_binary(), b.to_binary());
+        let a = super::ResTblReader::new(a.as_slice()).unwrap();
+        let b = super::ResTblReader::new(b.as_slice()).unwrap();
+        assert!(a.value_changes(&b).eq([(
+            crate::TableIndex::HashIndex(crate::util::hash_name(changed)),
+            1,
+            31880
+        )]));
+        assert!(b.value_changes(&b).next().is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn with_rest() {
+        let mut data = DATA.to_vec();
+        data.extend_from_slice(b"trailing");
+        let (table, rest) = crate::ResourceSizeTable::from_binary_with_rest(&data).unwrap();
+        assert_eq!(rest, b"trailing");
+        assert_eq!(table, crate::ResourceSizeTable::from_binary(DATA).unwrap());
+        let (_, rest) = crate::ResourceSizeTable::from_binary_with_rest(DATA).unwrap();
+        assert!(rest.is_empty());
+        assert!(crate::ResourceSizeTable::from_binary_with_rest(&DATA[..DATA.len() - 1]).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reuse() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut small = crate::ResourceSizeTable::new();
+        small.set(1, 2);
+        let mut buffer = Vec::new();
+        table.to_binary_reuse(&mut buffer);
+        assert_eq!(buffer, DATA);
+        let capacity = buffer.capacity();
+        small.to_binary_reuse(&mut buffer);
+        assert_eq!(buffer, small.to_binary());
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn aligned() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        for align in [1, 4, 0x20, 0x1000] {
+            let bytes = table.to_binary_aligned(align);
+            assert_eq!(bytes.len() % align, 0);
+            assert!(bytes.len() - DATA.len() < align);
+            assert_eq!(&bytes[..DATA.len()], DATA);
+            assert_eq!(
+                crate::ResourceSizeTable::from_binary(&bytes).unwrap(),
+                table
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn duplicates() {
+        let table = crate::ResourceSizeTable::from_binary_strict(DATA).unwrap();
+        assert_eq!(table, crate::ResourceSizeTable::from_binary(DATA).unwrap());
+        let mut builder = super::TableBuilder::new();
+        builder.push_hash(1, 10).unwrap();
+        builder.push_hash(2, 20).unwrap();
+        let mut data = builder.finish();
+        // Overwrite the second hash to duplicate the first
+        data[super::Header::FULL_SIZE + 8..][..4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            crate::ResourceSizeTable::from_binary(&data).unwrap().get(1),
+            Some(20)
+        );
+        assert!(matches!(
+            crate::ResourceSizeTable::from_binary_strict(&data),
+            Err(crate::Error::DuplicateHash(1))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn binary_eq() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut changed = table.clone();
+        changed.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        let mut added = table.clone();
+        added.set("Test/New.bgyml", 2);
+        let mut renamed = table.clone();
+        renamed
+            .rename("Bake/Scene/MainField_G_26_43.bkres", "Test/Renamed.bkres")
+            .unwrap();
+        let tables = [table, changed, added, renamed];
+        for a in &tables {
+            for b in &tables {
+                assert_eq!(a.binary_eq(b), a.to_binary() == b.to_binary());
+            }
         }
-        assert_eq!(DATA, bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn changed_entries() {
+        let base = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let moved = "Test/Moved.bgyml";
+        let mut a = base.clone();
+        a.name_table.insert(moved.into(), 5);
+        a.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        a.set("Test/OnlyA.bgyml", 2);
+        let mut b = base;
+        b.crc_table.insert(crate::util::hash_name(moved), 5);
+        b.set("Test/OnlyB.bgyml", 3);
+        let a_bytes = a.to_binary();
+        let b_bytes = b.to_binary();
+        let a = super::ResTblReader::new(a_bytes.as_slice()).unwrap();
+        let b = super::ResTblReader::new(b_bytes.as_slice()).unwrap();
+        let changes = a.changed_entries(&b);
+        assert_eq!(changes.len(), 3);
+        let hash = |name| crate::TableIndex::HashIndex(crate::util::hash_name(name));
+        let find = |idx: crate::TableIndex| {
+            changes
+                .iter()
+                .find(|(k, _, _)| *k == idx)
+                .map(|(_, a, b)| (*a, *b))
+        };
+        assert_eq!(
+            find(hash("Bake/Scene/MainField_G_26_43.bkres")),
+            Some((Some(1), Some(31880)))
+        );
+        assert_eq!(find(hash("Test/OnlyA.bgyml")), Some((Some(2), None)));
+        assert_eq!(find(hash("Test/OnlyB.bgyml")), Some((None, Some(3))));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn builder() {
+        let parser = super::ResTblReader::new(DATA).unwrap();
+        let mut builder = super::TableBuilder::with_capacity(
+            parser.header().crc_table_count() as usize,
+            parser.header().name_table_count() as usize,
+        );
+        for entry in parser.iter() {
+            match entry {
+                super::TableEntry::Hash(e) => builder.push_hash(e.hash(), e.value()).unwrap(),
+                super::TableEntry::Name(e) => builder.push_name(&e.name(), e.value()).unwrap(),
+            }
+        }
+        assert_eq!(DATA, builder.finish());
+
+        let mut builder = super::TableBuilder::new();
+        builder.push_hash(2, 0).unwrap();
+        assert!(matches!(
+            builder.push_hash(1, 0),
+            Err(crate::Error::Unsorted)
+        ));
+        builder.push_name("B", 0).unwrap();
+        assert!(matches!(
+            builder.push_name("A", 0),
+            Err(crate::Error::Unsorted)
+        ));
+        assert!(matches!(
+            builder.push_hash(3, 0),
+            Err(crate::Error::Unsorted)
+        ));
+        builder.push_name(&"C".repeat(159), 0).unwrap();
+        assert!(matches!(
+            builder.push_name(&"C".repeat(170), 0),
+            Err(crate::Error::NameTooLong(170))
+        ));
     }
 }
@@ -0,0 +1,179 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::mem::size_of;
+
+use crate::{
+    bin::{HashEntry, Header, NameEntry, ResTblReader},
+    util::{hash_name, read_u32, Name},
+    ResourceSizeTable, Result, TableIndex,
+};
+
+/// Editable RSTB table which borrows its resource names from the buffer it
+/// was parsed from, instead of copying each into a 160-byte [`Name`].
+///
+/// Otherwise this behaves like [`ResourceSizeTable`], except that new name
+/// entries cannot be added, since their names would not outlive the buffer.
+/// The saving is `size_of::<Name>() - size_of::<&str>()` bytes per name
+/// entry, so it only matters for name-heavy tables. TOTK's own table has just
+/// 32 name entries.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BorrowedTable<'a> {
+    pub crc_table: BTreeMap<u32, u32>,
+    pub name_table: BTreeMap<&'a str, u32>,
+}
+
+impl<'a> BorrowedTable<'a> {
+    /// Parse a table from binary form, borrowing names from the data
+    pub fn from_binary(data: &'a [u8]) -> Result<Self> {
+        let parser = ResTblReader::new(data)?;
+        let crc_count = parser.header().crc_table_count() as usize;
+        let name_count = parser.header().name_table_count() as usize;
+        let crc_table = (0..crc_count)
+            .map(|i| {
+                let offset = Header::FULL_SIZE + i * size_of::<HashEntry>();
+                Ok((
+                    read_u32(data, Some(offset))?,
+                    read_u32(data, Some(offset + 4))?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let name_offset = Header::FULL_SIZE + crc_count * size_of::<HashEntry>();
+        let name_table = (0..name_count)
+            .map(|i| {
+                let offset = name_offset + i * size_of::<NameEntry>();
+                let name = &data[offset..offset + size_of::<Name>()];
+                let len = name.iter().position(|c| *c == 0).unwrap_or(name.len());
+                let name = core::str::from_utf8(&name[..len])?;
+                Ok((name, read_u32(data, Some(offset + size_of::<Name>()))?))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            crc_table,
+            name_table,
+        })
+    }
+
+    /// Get the total number of hash and name entries in the table
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.crc_table.len() + self.name_table.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the specified hash or resource name is present in the table.
+    /// Checks the name table first (if applicable) and then the hash table.
+    pub fn contains<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
+        self.get(needle).is_some()
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name if
+    /// present. Checks the name table first (if applicable) and then the hash
+    /// table.
+    pub fn get<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
+        fn inner(tbl: &BorrowedTable, needle: TableIndex) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.get(&hash),
+                TableIndex::StringIndex(name) => tbl
+                    .name_table
+                    .get(name.as_ref())
+                    .or_else(|| tbl.crc_table.get(&hash_name(&name))),
+            }
+            .copied()
+        }
+        inner(self, needle.into())
+    }
+
+    /// Set the RSTB value for the specified hash or resource name, returning
+    /// the original value if present. A resource name updates an existing
+    /// name entry, and is otherwise stored as a hash entry.
+    pub fn set<'i, I: Into<TableIndex<'i>>>(&mut self, needle: I, value: u32) -> Option<u32> {
+        fn inner(tbl: &mut BorrowedTable, needle: TableIndex, value: u32) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.insert(hash, value),
+                TableIndex::StringIndex(name) => match tbl.name_table.get_mut(name.as_ref()) {
+                    Some(old) => Some(core::mem::replace(old, value)),
+                    None => tbl.crc_table.insert(hash_name(&name), value),
+                },
+            }
+        }
+        inner(self, needle.into(), value)
+    }
+
+    /// Remove the RSTB value for the specified hash or resource name, returning
+    /// the original value if present. Checks the name table first (if
+    /// applicable) and then the hash table.
+    pub fn remove<'i, I: Into<TableIndex<'i>>>(&mut self, needle: I) -> Option<u32> {
+        fn inner(tbl: &mut BorrowedTable, needle: TableIndex) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.remove(&hash),
+                TableIndex::StringIndex(name) => tbl
+                    .name_table
+                    .remove(name.as_ref())
+                    .or_else(|| tbl.crc_table.remove(&hash_name(&name))),
+            }
+        }
+        inner(self, needle.into())
+    }
+
+    /// Copy the table into an owned [`ResourceSizeTable`]
+    pub fn into_owned(self) -> ResourceSizeTable {
+        ResourceSizeTable {
+            crc_table: self.crc_table,
+            name_table: self
+                .name_table
+                .into_iter()
+                .map(|(name, value)| (Name::from(name), value))
+                .collect(),
+        }
+    }
+
+    /// Write the table in its binary format to bytes.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let size = Header::FULL_SIZE
+            + size_of::<HashEntry>() * self.crc_table.len()
+            + size_of::<NameEntry>() * self.name_table.len();
+        let mut buffer = alloc::vec![0u8; size];
+        Header::with_counts(self.crc_table.len() as u32, self.name_table.len() as u32)
+            .write(&mut buffer);
+        let mut pos = Header::FULL_SIZE;
+        for (hash, value) in &self.crc_table {
+            HashEntry::new(*hash, *value).write(&mut buffer[pos..]);
+            pos += size_of::<HashEntry>();
+        }
+        for (name, value) in &self.name_table {
+            NameEntry::new(Name::from(*name), *value).write(&mut buffer[pos..]);
+            pos += size_of::<NameEntry>();
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BorrowedTable;
+    use crate::{test::DATA, ResourceSizeTable};
+
+    #[test]
+    fn borrowed() {
+        let mut table = BorrowedTable::from_binary(DATA).unwrap();
+        let owned = ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(table.to_binary(), DATA);
+        assert_eq!(table.clone().into_owned(), owned);
+        let data = DATA.as_ptr_range();
+        assert!(table
+            .name_table
+            .keys()
+            .all(|name| data.contains(&name.as_ptr())));
+        let name = *table.name_table.keys().next().unwrap();
+        assert_eq!(table.set(name, 1), owned.get(name));
+        assert_eq!(table.get(name), Some(1));
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        assert_eq!(table.set(hashed, 2), Some(31880));
+        assert_eq!(table.remove(hashed), Some(2));
+        assert!(!table.contains(hashed));
+        assert_eq!(table.len(), owned.len() - 1);
+    }
+}
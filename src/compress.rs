@@ -0,0 +1,141 @@
+//! Reading and writing zstd compressed tables, as the game ships them.
+
+use alloc::vec::Vec;
+use std::path::Path;
+
+use crate::{bin::ResTblReader, util, OwnedKey, ResourceSizeTable, Result};
+
+/// The zstd compression level used when writing tables
+const LEVEL: i32 = 19;
+
+/// Decompress `data` if it starts with a zstd frame, otherwise return it as is
+fn decompress_if_needed(data: Vec<u8>) -> Result<Vec<u8>> {
+    if data.starts_with(&util::ZSTD_MAGIC) {
+        Ok(zstd::decode_all(data.as_slice())?)
+    } else {
+        Ok(data)
+    }
+}
+
+impl ResourceSizeTable {
+    /// Parse a table from zstd compressed binary form, such as the contents of
+    /// `ResourceSizeTable.Product.<version>.rsizetable.zs`
+    pub fn from_compressed(data: impl AsRef<[u8]>) -> Result<Self> {
+        Self::from_binary(zstd::decode_all(data.as_ref())?)
+    }
+
+    /// Write the table in its binary format to zstd compressed bytes
+    pub fn to_compressed(&self) -> Result<Vec<u8>> {
+        Ok(zstd::encode_all(self.to_binary().as_slice(), LEVEL)?)
+    }
+
+    /// Produce a sparse patch turning a compressed base table, such as the
+    /// game's own file, into this one. The patch is a table in the usual
+    /// binary format holding only the entries which this table adds or changes
+    /// relative to the base, as found by [`diff`](Self::diff), so merging it
+    /// into the base with [`merge`](Self::merge) gives this table's values.
+    /// Entries removed from the base cannot be expressed and are left out.
+    pub fn patch_against_compressed(&self, base_zs: &[u8]) -> Result<Vec<u8>> {
+        let diff = Self::from_compressed(base_zs)?.diff(self);
+        let mut patch = Self::new();
+        for (key, value) in diff
+            .added
+            .iter()
+            .chain(diff.changed.iter().map(|(key, (_, new))| (key, new)))
+        {
+            match key {
+                OwnedKey::Hash(hash) => patch.crc_table.insert(*hash, *value),
+                OwnedKey::Name(name) => patch.name_table.insert(*name, *value),
+            };
+        }
+        Ok(patch.to_binary())
+    }
+
+    /// Write the table in its zstd compressed binary format to a file,
+    /// replacing it atomically as by [`save`](Self::save)
+    pub fn save_compressed(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = self.to_compressed()?;
+        util::write_atomic(path.as_ref(), |file| {
+            Ok(std::io::Write::write_all(file, &data)?)
+        })
+    }
+}
+
+/// Convert an RSTB file between its compressed and uncompressed forms.
+///
+/// The input may be either form, detected from its contents rather than its
+/// name. It is parsed before anything is written, so an invalid table fails
+/// without touching `output`. The output is compressed if its path ends in
+/// `.zs` and written uncompressed otherwise, replacing any existing file
+/// atomically as by [`ResourceSizeTable::save`]. The table bytes are copied
+/// exactly, not re-serialized.
+pub fn recompress(input: &Path, output: &Path) -> Result<()> {
+    let data = decompress_if_needed(std::fs::read(input)?)?;
+    ResTblReader::new(&data)?;
+    let data = if output.extension().is_some_and(|ext| ext == "zs") {
+        zstd::encode_all(data.as_slice(), LEVEL)?
+    } else {
+        data
+    };
+    util::write_atomic(output, |file| Ok(std::io::Write::write_all(file, &data)?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::DATA, ResourceSizeTable};
+
+    #[test]
+    fn compressed() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let compressed = table.to_compressed().unwrap();
+        assert!(compressed.len() < DATA.len());
+        assert_eq!(
+            ResourceSizeTable::from_compressed(&compressed).unwrap(),
+            table
+        );
+        assert!(ResourceSizeTable::from_compressed(DATA).is_err());
+    }
+
+    #[test]
+    fn patch_against_compressed() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let base = table.to_compressed().unwrap();
+        let patch = table.patch_against_compressed(&base).unwrap();
+        assert!(ResourceSizeTable::from_binary(patch).unwrap().is_empty());
+        let mut edited = table.clone();
+        edited.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        edited.set("Test/New.bgyml", 2);
+        let patch = edited.patch_against_compressed(&base).unwrap();
+        let patch = ResourceSizeTable::from_binary(patch).unwrap();
+        assert_eq!(patch.len(), 2);
+        let mut patched = table;
+        patched.merge(&patch);
+        assert_eq!(patched, edited);
+        assert!(edited.patch_against_compressed(DATA).is_err());
+    }
+
+    #[test]
+    fn recompress() {
+        let dir = crate::test::temp_dir("recompress");
+        let raw = dir.join("Test.rsizetable");
+        let compressed = dir.join("Test.rsizetable.zs");
+        let roundtrip = dir.join("Roundtrip.rsizetable");
+        std::fs::write(&raw, DATA).unwrap();
+        super::recompress(&raw, &compressed).unwrap();
+        super::recompress(&compressed, &roundtrip).unwrap();
+        let compressed_table =
+            ResourceSizeTable::from_compressed(std::fs::read(&compressed).unwrap());
+        let roundtrip_data = std::fs::read(&roundtrip).unwrap();
+        std::fs::write(&raw, &DATA[..DATA.len() - 1]).unwrap();
+        let invalid = super::recompress(&raw, &dir.join("Invalid.rsizetable.zs"));
+        let invalid_written = dir.join("Invalid.rsizetable.zs").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            compressed_table.unwrap(),
+            ResourceSizeTable::from_binary(DATA).unwrap()
+        );
+        assert_eq!(roundtrip_data, DATA);
+        assert!(invalid.is_err());
+        assert!(!invalid_written);
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::string::String;
+
+use crate::{estimate::extension, util::hash_name, ResourceSizeTable};
+
+#[cfg(feature = "std")]
+type Map = std::collections::HashMap<u32, String>;
+#[cfg(not(feature = "std"))]
+type Map = alloc::collections::BTreeMap<u32, String>;
+
+/// Dictionary of known resource names, keyed by their hashes, for recovering
+/// the names behind hash table entries
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NameDict {
+    names: Map,
+}
+
+impl NameDict {
+    /// Construct an empty dictionary
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a resource name, returning its hash. If another name with the same
+    /// hash is already known, it is replaced.
+    pub fn insert(&mut self, name: impl Into<String>) -> u32 {
+        let name = name.into();
+        let hash = hash_name(&name);
+        self.names.insert(hash, name);
+        hash
+    }
+
+    /// Returns the known resource name for a hash, if any
+    #[inline]
+    pub fn get(&self, hash: u32) -> Option<&str> {
+        self.names.get(&hash).map(|name| name.as_str())
+    }
+
+    /// Get the number of names in the dictionary
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl<S: Into<String>> Extend<S> for NameDict {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        for name in iter {
+            self.insert(name);
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for NameDict {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut dict = Self::new();
+        dict.extend(iter);
+        dict
+    }
+}
+
+impl ResourceSizeTable {
+    /// Set the value of every entry for a resource with the given extension
+    /// (with or without the leading dot), returning the number of entries
+    /// updated. Name entries are matched by their own names and hash entries
+    /// by their names in `dict`; hashes the dictionary does not know are left
+    /// alone.
+    pub fn set_by_extension(&mut self, ext: &str, value: u32, dict: &NameDict) -> usize {
+        let ext = ext.strip_prefix('.').unwrap_or(ext);
+        let mut count = 0;
+        for (hash, old) in self.crc_table.iter_mut() {
+            if dict.get(*hash).and_then(extension) == Some(ext) {
+                *old = value;
+                count += 1;
+            }
+        }
+        for (name, old) in self.name_table.iter_mut() {
+            if extension(name.as_str()) == Some(ext) {
+                *old = value;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+/// The key of an RSTB entry after resolving it against a [`NameDict`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResolvedKey<'a> {
+    /// The resource name, from a name entry or a known hash
+    Name(&'a str),
+    /// A hash the dictionary does not know
+    Hash(u32),
+}
+
+impl core::fmt::Display for ResolvedKey<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Name(name) => name.fmt(f),
+            Self::Hash(hash) => hash.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NameDict, ResolvedKey};
+    use crate::{bin::ResTblReader, test::DATA, util::hash_name};
+
+    #[test]
+    fn iter_resolved() {
+        let known = "Bake/Scene/MainField_G_26_43.bkres";
+        let dict = NameDict::from_iter([known]);
+        assert_eq!(dict.get(hash_name(known)), Some(known));
+        let parser = ResTblReader::new(DATA).unwrap();
+        let resolved = parser.iter_resolved(&dict).collect::<Vec<_>>();
+        assert_eq!(resolved.len(), parser.len());
+        assert!(resolved.contains(&(ResolvedKey::Name(known), 31880)));
+        assert!(resolved
+            .iter()
+            .any(|(key, _)| matches!(key, ResolvedKey::Hash(_))));
+        for (name, value) in parser.iter_name_strs() {
+            assert!(resolved.contains(&(ResolvedKey::Name(name), value)));
+        }
+    }
+
+    #[test]
+    fn name_paths() {
+        let parser = ResTblReader::new(DATA).unwrap();
+        let dict = parser.name_paths().collect::<NameDict>();
+        assert!(!dict.is_empty());
+        let table = crate::ResourceSizeTable::from_parser(&parser);
+        assert!(parser.name_paths().eq(table.name_paths()));
+        for name in table.name_paths() {
+            assert!(dict.get(hash_name(name)).is_some());
+        }
+    }
+
+    #[test]
+    fn set_by_extension() {
+        let mut table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        let dict = NameDict::from_iter([hashed]);
+        let is_bkres = |name: &str| name.ends_with(".bkres");
+        let names = table.name_paths().filter(|name| is_bkres(name)).count();
+        assert!(names > 0);
+        assert_eq!(table.set_by_extension(".bkres", 7, &dict), names + 1);
+        assert_eq!(table.get(hashed), Some(7));
+        for (name, value) in &table.name_table {
+            assert_eq!(*value == 7, is_bkres(name.as_str()));
+        }
+        let before = table.clone();
+        assert_eq!(table.set_by_extension("nonexistent", 1, &dict), 0);
+        assert_eq!(table, before);
+    }
+}
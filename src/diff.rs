@@ -0,0 +1,323 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::{util::hash_name, Name, NameDict, OwnedKey, ResourceSizeTable};
+
+/// The differences between two RSTB tables, as the changes needed to turn a
+/// base table into a new one
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RstbDiff {
+    /// Entries present only in the new table
+    pub added: BTreeMap<OwnedKey, u32>,
+    /// Entries present only in the base table
+    pub removed: BTreeMap<OwnedKey, u32>,
+    /// Entries present in both tables with different values, as `(base, new)`
+    pub changed: BTreeMap<OwnedKey, (u32, u32)>,
+}
+
+/// Counts of the changes in an [`RstbDiff`] for a group of entries
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+impl RstbDiff {
+    /// Get the total number of added, removed, and changed entries
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate every differing key in sorted order, with its value in the base
+    /// and new tables (`None` if absent)
+    pub fn iter(&self) -> impl Iterator<Item = (&OwnedKey, Option<u32>, Option<u32>)> {
+        let mut all = BTreeMap::new();
+        for (key, value) in &self.removed {
+            all.insert(key, (Some(*value), None));
+        }
+        for (key, (base, new)) in &self.changed {
+            all.insert(key, (Some(*base), Some(*new)));
+        }
+        for (key, value) in &self.added {
+            all.insert(key, (None, Some(*value)));
+        }
+        all.into_iter().map(|(key, (base, new))| (key, base, new))
+    }
+
+    /// Count the changes for each resource file extension (without the leading
+    /// dot), resolving hash keys to names with a dictionary. Entries whose
+    /// names are unknown or have no extension are counted under `"unknown"`.
+    pub fn by_extension(&self, dict: &NameDict) -> BTreeMap<String, DiffStats> {
+        fn extension<'a>(key: &'a OwnedKey, dict: &'a NameDict) -> &'a str {
+            let name = match key {
+                OwnedKey::Name(name) => Some(name.as_str()),
+                OwnedKey::Hash(hash) => dict.get(*hash),
+            };
+            name.and_then(crate::estimate::extension)
+                .unwrap_or("unknown")
+        }
+        let mut stats = BTreeMap::<String, DiffStats>::new();
+        for key in self.added.keys() {
+            stats.entry(extension(key, dict).into()).or_default().added += 1;
+        }
+        for key in self.removed.keys() {
+            stats
+                .entry(extension(key, dict).into())
+                .or_default()
+                .removed += 1;
+        }
+        for key in self.changed.keys() {
+            stats
+                .entry(extension(key, dict).into())
+                .or_default()
+                .changed += 1;
+        }
+        stats
+    }
+}
+
+impl ResourceSizeTable {
+    /// Compute the changes needed to turn this table into `other`.
+    ///
+    /// A resource stored as a name entry in one table and as a hash entry in
+    /// the other is treated as the same entry, unless the table storing the
+    /// name also has a separate entry for its hash.
+    pub fn diff(&self, other: &ResourceSizeTable) -> RstbDiff {
+        let mut diff = RstbDiff::default();
+        let (names, other_names) = (self.name_hash_index(), other.name_hash_index());
+        for (key, value) in self.owned_entries() {
+            match counterpart_value(self, other, &other_names, &key) {
+                Some(new) if new == value => (),
+                Some(new) => {
+                    diff.changed.insert(key, (value, new));
+                }
+                None => {
+                    diff.removed.insert(key, value);
+                }
+            }
+        }
+        for (key, value) in other.owned_entries() {
+            if counterpart_value(other, self, &names, &key).is_none() {
+                diff.added.insert(key, value);
+            }
+        }
+        diff
+    }
+
+    /// Remove every entry which is also present in `other`, whatever its
+    /// value, returning the number of entries removed. Entries are matched as
+    /// by [`diff`](Self::diff), so a resource stored as a name in one table
+    /// and as a hash in the other still counts as present.
+    pub fn subtract(&mut self, other: &ResourceSizeTable) -> usize {
+        let other_names = other.name_hash_index();
+        let present = self
+            .owned_entries()
+            .filter(|(key, _)| counterpart_value(self, other, &other_names, key).is_some())
+            .map(|(key, _)| key)
+            .collect::<alloc::vec::Vec<_>>();
+        for key in &present {
+            match key {
+                OwnedKey::Hash(hash) => self.crc_table.remove(hash),
+                OwnedKey::Name(name) => self.name_table.remove(name),
+            };
+        }
+        present.len()
+    }
+
+    /// Copy every entry of `base` which is missing from this table, without
+    /// changing any existing value, returning the number of entries added.
+    /// This completes a sparse patch with the base game's values. Entries are
+    /// matched as by [`diff`](Self::diff), so a resource this table stores as
+    /// a hash is not added again from a name entry in `base`.
+    pub fn fill_missing_from(&mut self, base: &ResourceSizeTable) -> usize {
+        let names = self.name_hash_index();
+        let missing = base
+            .owned_entries()
+            .filter(|(key, _)| counterpart_value(base, self, &names, key).is_none())
+            .collect::<alloc::vec::Vec<_>>();
+        for (key, value) in &missing {
+            match key {
+                OwnedKey::Hash(hash) => self.crc_table.insert(*hash, *value),
+                OwnedKey::Name(name) => self.name_table.insert(*name, *value),
+            };
+        }
+        missing.len()
+    }
+
+    fn owned_entries(&self) -> impl Iterator<Item = (OwnedKey, u32)> + '_ {
+        self.crc_table
+            .iter()
+            .map(|(hash, value)| (OwnedKey::Hash(*hash), *value))
+            .chain(
+                self.name_table
+                    .iter()
+                    .map(|(name, value)| (OwnedKey::Name(*name), *value)),
+            )
+    }
+
+    fn name_hash_index(&self) -> NameHashIndex {
+        NameHashIndex::new(self.name_table.iter().map(|(name, value)| (*name, *value)))
+    }
+}
+
+impl EntryLookup for ResourceSizeTable {
+    fn hash_value(&self, hash: u32) -> Option<u32> {
+        self.crc_table.get(&hash).copied()
+    }
+
+    fn name_value(&self, name: &Name) -> Option<u32> {
+        self.name_table.get(name).copied()
+    }
+}
+
+/// The lookups needed to match entries between two tables with
+/// [`counterpart_value`]
+pub(crate) trait EntryLookup {
+    fn hash_value(&self, hash: u32) -> Option<u32>;
+    fn name_value(&self, name: &Name) -> Option<u32>;
+}
+
+/// The name entries of a table sorted by hash, to find the name entries for
+/// a hash without hashing every name
+pub(crate) struct NameHashIndex(Vec<(u32, Name, u32)>);
+
+impl NameHashIndex {
+    pub(crate) fn new(names: impl Iterator<Item = (Name, u32)>) -> Self {
+        let mut index = names
+            .map(|(name, value)| (hash_name(&name), name, value))
+            .collect::<Vec<_>>();
+        index.sort_by_key(|(hash, ..)| *hash);
+        Self(index)
+    }
+
+    fn with_hash(&self, hash: u32) -> impl Iterator<Item = &(u32, Name, u32)> {
+        let start = self.0.partition_point(|(h, ..)| *h < hash);
+        self.0[start..].iter().take_while(move |(h, ..)| *h == hash)
+    }
+}
+
+/// Returns the value of the entry in `other` corresponding to `key` from
+/// `this`, treating an entry moved between the name and hash tables as the
+/// same entry. `other_names` must index the name entries of `other`.
+pub(crate) fn counterpart_value(
+    this: &impl EntryLookup,
+    other: &impl EntryLookup,
+    other_names: &NameHashIndex,
+    key: &OwnedKey,
+) -> Option<u32> {
+    match key {
+        OwnedKey::Hash(hash) => other.hash_value(*hash).or_else(|| {
+            other_names
+                .with_hash(*hash)
+                .find(|(_, name, _)| this.name_value(name).is_none())
+                .map(|(.., value)| *value)
+        }),
+        OwnedKey::Name(name) => other.name_value(name).or_else(|| {
+            let hash = hash_name(name);
+            this.hash_value(hash)
+                .is_none()
+                .then(|| other.hash_value(hash))
+                .flatten()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::DATA, OwnedKey, ResourceSizeTable};
+
+    #[test]
+    fn subtract() {
+        let base = ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut superset = base.clone();
+        superset.set("Test/New.bgyml", 1);
+        superset.set("Bake/Scene/MainField_G_26_43.bkres", 2);
+        let mut subset = base.clone();
+        assert_eq!(subset.subtract(&superset), base.len());
+        assert!(subset.is_empty());
+        assert_eq!(superset.subtract(&base), base.len());
+        assert_eq!(superset.len(), 1);
+        assert_eq!(superset.get("Test/New.bgyml"), Some(1));
+    }
+
+    #[test]
+    fn fill_missing_from() {
+        let mut base = ResourceSizeTable::from_binary(DATA).unwrap();
+        let name = "Test/Named.bgyml";
+        base.name_table.insert(name.into(), 4);
+        let mut patch = ResourceSizeTable::new();
+        patch.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        patch.set("Test/New.bgyml", 2);
+        // Stored by hash here but by name in the base
+        patch.crc_table.insert(crate::util::hash_name(name), 3);
+        assert_eq!(patch.fill_missing_from(&base), base.len() - 2);
+        assert_eq!(patch.len(), base.len() + 1);
+        assert_eq!(patch.get("Bake/Scene/MainField_G_26_43.bkres"), Some(1));
+        assert_eq!(patch.get("Test/New.bgyml"), Some(2));
+        assert!(!patch.name_table.contains_key(&name.into()));
+        assert_eq!(patch.fill_missing_from(&base), 0);
+    }
+
+    #[test]
+    fn diff() {
+        let base = ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(base.diff(&base).is_empty());
+        let moved = "Test/Moved.bgyml";
+        let mut a = base.clone();
+        a.name_table.insert(moved.into(), 5);
+        a.remove("Bake/Scene/MainField_G_26_43.bkres");
+        a.set("Test/Removed.bgyml", 3);
+        let mut b = base;
+        b.crc_table.insert(crate::util::hash_name(moved), 5);
+        b.set("Bake/Scene/MainField_U_30_50.bkres", 1);
+        b.set("Test/New.bgyml", 2);
+        let diff = a.diff(&b);
+        assert_eq!(diff.len(), 4);
+        let hash = |name| OwnedKey::Hash(crate::util::hash_name(name));
+        assert_eq!(
+            diff.added.get(&hash("Bake/Scene/MainField_G_26_43.bkres")),
+            Some(&31880)
+        );
+        assert_eq!(
+            diff.changed
+                .get(&OwnedKey::from("Bake/Scene/MainField_U_30_50.bkres")),
+            Some(&(64416, 1))
+        );
+        assert_eq!(diff.added.get(&hash("Test/New.bgyml")), Some(&2));
+        assert_eq!(diff.removed.get(&hash("Test/Removed.bgyml")), Some(&3));
+
+        let dict = crate::NameDict::from_iter(["Test/New.bgyml", "Test/Removed.bgyml"]);
+        let stats = diff.by_extension(&dict);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats["bgyml"],
+            super::DiffStats {
+                added: 1,
+                removed: 1,
+                changed: 0
+            }
+        );
+        assert_eq!(
+            stats["bkres"],
+            super::DiffStats {
+                added: 0,
+                removed: 0,
+                changed: 1
+            }
+        );
+        assert_eq!(
+            stats["unknown"],
+            super::DiffStats {
+                added: 1,
+                removed: 0,
+                changed: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,407 @@
+//! Heuristic estimation of RSTB values from decompressed resource sizes.
+//!
+//! The game does not store how it derives RSTB values, so these formulas are
+//! conservative approximations rather than exact reproductions. Every known
+//! resource type uses `(round_up(size, 32) + 1500) * 4`, except BYML-based
+//! formats (`.bgyml`, `.byml`), which use `(round_up(size, 32) + 1000) * 8`
+//! because their parsed form is much larger than the file. Paths with an
+//! unrecognized extension cannot be estimated.
+
+/// Extensions of the resource types found in TOTK's RSTB, without the leading
+/// dot, in sorted order. The estimator has a formula for each of them.
+/// TerrainArc archives, which the table lists still compressed as `.ta.zs`,
+/// are not included.
+pub const KNOWN_EXTENSIONS: &[&str] = &[
+    "ainb",
+    "asb",
+    "baatarc",
+    "baev",
+    "bagst",
+    "bars",
+    "bcul",
+    "beco",
+    "belnk",
+    "bfarc",
+    "bfevfl",
+    "bfres",
+    "bfsha",
+    "bgyml",
+    "bhtmp",
+    "bkres",
+    "blal",
+    "blarc",
+    "blwp",
+    "bnvib",
+    "bpflt",
+    "bphcl",
+    "bphhb",
+    "bphnm",
+    "bphsc",
+    "bphsh",
+    "bslnk",
+    "bstar",
+    "byml",
+    "cai",
+    "chunk",
+    "crbin",
+    "cultinfo",
+    "esetb",
+    "genvb",
+    "mc",
+    "pack",
+    "quad",
+    "rsizetable",
+    "sarc",
+    "tscb",
+    "txtg",
+    "vsts",
+    "wbr",
+];
+
+// Lookups use a binary search, so the list must stay sorted and unique
+const _: () = assert!(is_sorted_unique(KNOWN_EXTENSIONS));
+
+const fn is_sorted_unique(list: &[&str]) -> bool {
+    let mut i = 1;
+    while i < list.len() {
+        let (a, b) = (list[i - 1].as_bytes(), list[i].as_bytes());
+        let mut j = 0;
+        loop {
+            if j == a.len() && j == b.len() {
+                return false;
+            } else if j == a.len() || (j < b.len() && a[j] < b[j]) {
+                break;
+            } else if j == b.len() || a[j] > b[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Check if a resource path ends in one of the [`KNOWN_EXTENSIONS`]
+pub fn extension_is_known(path: &str) -> bool {
+    extension(path).is_some_and(|ext| KNOWN_EXTENSIONS.binary_search(&ext).is_ok())
+}
+
+/// Returns the extension of the final component of a resource path, if any
+pub(crate) fn extension(path: &str) -> Option<&str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+/// Estimate the RSTB value for a resource from its canonical path and its
+/// decompressed size in bytes. Returns `None` if the extension is not one
+/// the estimator knows about. Values that would overflow saturate at
+/// `u32::MAX`.
+pub fn estimate_size(path: &str, decompressed_len: usize) -> Option<u32> {
+    if !extension_is_known(path) {
+        return None;
+    }
+    let ext = extension(path)?;
+    let rounded = (decompressed_len as u64).saturating_add(31) & !31;
+    let estimate = match ext {
+        "bgyml" | "byml" => rounded.saturating_add(1000).saturating_mul(8),
+        _ => rounded.saturating_add(1500).saturating_mul(4),
+    };
+    Some(estimate.min(u32::MAX as u64) as u32)
+}
+
+/// Check if a stored RSTB value is at least the estimate for a resource, given
+/// its canonical path and decompressed size in bytes. Returns `None` if the
+/// extension is not one the estimator knows about. See [`estimate_size`].
+pub fn is_sufficient(path: &str, decompressed_len: usize, stored_value: u32) -> Option<bool> {
+    estimate_size(path, decompressed_len).map(|estimate| stored_value >= estimate)
+}
+
+/// Returns how much must be added to a stored RSTB value to reach the estimate
+/// for a resource, given its canonical path and decompressed size in bytes, or
+/// 0 if the value is already sufficient. Returns `None` if the extension is
+/// not one the estimator knows about. See [`estimate_size`].
+pub fn required_delta(path: &str, decompressed_len: usize, current_value: u32) -> Option<u32> {
+    estimate_size(path, decompressed_len).map(|estimate| estimate.saturating_sub(current_value))
+}
+
+/// Configurable wrapper around [`estimate_size`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Estimator {
+    /// Extra bytes added to every estimate as a safety margin
+    pub margin: u32,
+}
+
+impl Estimator {
+    /// Construct an estimator with no extra margin
+    pub const fn new() -> Self {
+        Self { margin: 0 }
+    }
+
+    /// Construct an estimator which adds `margin` bytes to every estimate
+    pub const fn with_margin(margin: u32) -> Self {
+        Self { margin }
+    }
+
+    /// Estimate the RSTB value for a resource, including the margin. See
+    /// [`estimate_size`].
+    pub fn estimate(&self, path: &str, decompressed_len: usize) -> Option<u32> {
+        estimate_size(path, decompressed_len).map(|size| size.saturating_add(self.margin))
+    }
+}
+
+/// The result of checking a table against a list of files with
+/// [`ResourceSizeTable::check_manifest`](crate::ResourceSizeTable::check_manifest).
+/// Paths are reported as they were given.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ManifestReport {
+    /// Files with no entry in the table
+    pub missing: alloc::vec::Vec<alloc::string::String>,
+    /// Files whose entry is below the estimate, as `(path, value, estimate)`
+    pub undersized: alloc::vec::Vec<(alloc::string::String, u32, u32)>,
+    /// The number of files with an entry whose value could not be checked,
+    /// because the estimator does not know their extension
+    pub unchecked: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl ManifestReport {
+    /// Check if every file has an entry and no entry is known to be too small
+    #[inline(always)]
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.undersized.is_empty()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl super::ResourceSizeTable {
+    /// Check the table against a listing of the files a mod ships, as
+    /// `(path, decompressed_len)` pairs, reporting every file with no entry
+    /// and every entry whose value is below the estimator's estimate.
+    ///
+    /// Paths are looked up after converting them with
+    /// [`canonicalize_path`](crate::path::canonicalize_path), so paths to
+    /// compressed files on disk may be given directly. Files the estimator
+    /// cannot estimate are only checked for an entry.
+    pub fn check_manifest<'p>(
+        &self,
+        files: impl Iterator<Item = (&'p str, usize)>,
+        estimator: &Estimator,
+    ) -> ManifestReport {
+        let mut report = ManifestReport::default();
+        for (path, decompressed_len) in files {
+            let canon = crate::path::canonicalize_path(path);
+            let Some(value) = self.get(canon.as_ref()) else {
+                report.missing.push(path.into());
+                continue;
+            };
+            match estimator.estimate(&canon, decompressed_len) {
+                Some(estimate) if value < estimate => {
+                    report.undersized.push((path.into(), value, estimate))
+                }
+                Some(_) => (),
+                None => report.unchecked += 1,
+            }
+        }
+        report
+    }
+}
+
+#[cfg(feature = "std")]
+impl super::ResourceSizeTable {
+    /// Walk a directory of resources and set the estimated RSTB value for
+    /// every file the estimator recognizes, returning the number of entries
+    /// set.
+    ///
+    /// Each file's canonical resource path is its path relative to `root`,
+    /// with components joined by forward slashes and any trailing `.zs`
+    /// removed. For `.zs` files the decompressed size is read from the zstd
+    /// frame header, so files without a recorded content size are skipped, as
+    /// are files the estimator cannot estimate. Symlinked directories are
+    /// not walked.
+    pub fn populate_from_dir(
+        &mut self,
+        root: impl AsRef<std::path::Path>,
+        estimator: &Estimator,
+    ) -> crate::Result<usize> {
+        fn walk(
+            tbl: &mut super::ResourceSizeTable,
+            root: &std::path::Path,
+            dir: &std::path::Path,
+            estimator: &Estimator,
+        ) -> crate::Result<usize> {
+            let mut count = 0;
+            for entry in std::fs::read_dir(dir)? {
+                // Symlinks are not followed, so a link to a parent directory
+                // cannot recurse forever
+                let entry = entry?;
+                let path = entry.path();
+                if entry.file_type()?.is_dir() {
+                    count += walk(tbl, root, &path, estimator)?;
+                    continue;
+                }
+                let Some(canon) = path.strip_prefix(root).ok().and_then(|rel| {
+                    rel.iter()
+                        .map(|c| c.to_str())
+                        .collect::<Option<Vec<_>>>()
+                        .map(|parts| parts.join("/"))
+                }) else {
+                    continue;
+                };
+                let (canon, size) = match canon.strip_suffix(".zs") {
+                    Some(stripped) => {
+                        let mut header = Vec::with_capacity(18);
+                        let file = std::fs::File::open(&path)?;
+                        std::io::Read::read_to_end(
+                            &mut std::io::Read::take(file, 18),
+                            &mut header,
+                        )?;
+                        match crate::util::zstd_content_size(&header) {
+                            Some(size) => (stripped.to_owned(), size as usize),
+                            None => continue,
+                        }
+                    }
+                    None => {
+                        let size = std::fs::metadata(&path)?.len() as usize;
+                        (canon, size)
+                    }
+                };
+                if let Some(value) = estimator.estimate(&canon, size) {
+                    tbl.set(canon, value);
+                    count += 1;
+                }
+            }
+            Ok(count)
+        }
+        let root = root.as_ref();
+        walk(self, root, root, estimator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn known_extensions() {
+        assert!(KNOWN_EXTENSIONS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(is_sorted_unique(&["a", "ab", "b"]));
+        assert!(!is_sorted_unique(&["ab", "a"]));
+        assert!(!is_sorted_unique(&["a", "a"]));
+        assert!(extension_is_known("Pack/Actor/Test.pack"));
+        assert!(extension_is_known("Bake/Scene/MainField_G_26_43.bkres"));
+        assert!(!extension_is_known("Pack/Actor/Test.pack.zs"));
+        assert!(!extension_is_known("Sound/Test.unknown"));
+        assert!(!extension_is_known("Dir.pack/NoExtension"));
+        let parser = crate::bin::ResTblReader::new(crate::test::DATA).unwrap();
+        // TerrainArc archives are listed still compressed, as `.ta.zs`
+        assert!(parser
+            .iter_name_strs()
+            .filter(|(name, _)| !name.ends_with(".ta.zs"))
+            .all(|(name, _)| extension_is_known(name)));
+    }
+
+    #[test]
+    fn estimate() {
+        assert_eq!(
+            estimate_size("Pack/Actor/Test.pack", 1),
+            Some((32 + 1500) * 4)
+        );
+        assert_eq!(
+            estimate_size("GameData/Test.game__Test.bgyml", 64),
+            Some((64 + 1000) * 8)
+        );
+        assert_eq!(estimate_size("Sound/Test.unknown", 64), None);
+        assert_eq!(estimate_size("NoExtension", 64), None);
+        assert_eq!(estimate_size("Test.bfres", usize::MAX / 2), Some(u32::MAX));
+        assert_eq!(
+            Estimator::with_margin(100).estimate("Pack/Actor/Test.pack", 1),
+            Some((32 + 1500) * 4 + 100)
+        );
+    }
+
+    #[test]
+    fn sufficient() {
+        let estimate = estimate_size("Pack/Actor/Test.pack", 100).unwrap();
+        assert_eq!(
+            is_sufficient("Pack/Actor/Test.pack", 100, estimate),
+            Some(true)
+        );
+        assert_eq!(
+            is_sufficient("Pack/Actor/Test.pack", 100, estimate - 1),
+            Some(false)
+        );
+        assert_eq!(is_sufficient("Sound/Test.unknown", 100, 0), None);
+        assert_eq!(
+            required_delta("Pack/Actor/Test.pack", 100, estimate - 10),
+            Some(10)
+        );
+        assert_eq!(
+            required_delta("Pack/Actor/Test.pack", 100, estimate),
+            Some(0)
+        );
+        assert_eq!(
+            required_delta("Pack/Actor/Test.pack", 100, u32::MAX),
+            Some(0)
+        );
+        assert_eq!(required_delta("Sound/Test.unknown", 100, 0), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn check_manifest() {
+        let mut table = crate::ResourceSizeTable::new();
+        let estimate = estimate_size("Pack/Actor/Test.pack", 100).unwrap();
+        table.set("Pack/Actor/Test.pack", estimate);
+        table.set("Pack/Actor/Small.pack", estimate - 1);
+        table.set("Sound/Test.unknown", 1);
+        let files = [
+            ("Pack/Actor/Test.pack.zs", 100),
+            ("./Pack/Actor/Small.pack", 100),
+            ("Sound/Test.unknown", 100),
+            ("Pack/Actor/Missing.pack", 100),
+        ];
+        let report = table.check_manifest(files.into_iter(), &Estimator::new());
+        assert!(!report.is_ok());
+        assert_eq!(report.missing, ["Pack/Actor/Missing.pack"]);
+        assert_eq!(
+            report.undersized,
+            [("./Pack/Actor/Small.pack".into(), estimate - 1, estimate)]
+        );
+        assert_eq!(report.unchecked, 1);
+        let report = table.check_manifest(files[..1].iter().copied(), &Estimator::new());
+        assert!(report.is_ok());
+        let report = table.check_manifest(files[..1].iter().copied(), &Estimator::with_margin(1));
+        assert_eq!(report.undersized.len(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn populate_from_dir() {
+        let root = crate::test::temp_dir("populate_from_dir");
+        std::fs::create_dir_all(root.join("Pack/Actor")).unwrap();
+        std::fs::write(root.join("Pack/Actor/Test.pack"), [0u8; 100]).unwrap();
+        // Just a zstd frame header: single segment with a 1-byte content size
+        std::fs::write(
+            root.join("Pack/Actor/Compressed.pack.zs"),
+            [0x28, 0xb5, 0x2f, 0xfd, 0x20, 200],
+        )
+        .unwrap();
+        std::fs::write(root.join("Readme.unknown"), b"hi").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&root, root.join("Pack/Loop")).unwrap();
+        let mut table = crate::ResourceSizeTable::new();
+        let count = table.populate_from_dir(&root, &Estimator::new()).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            table.get("Pack/Actor/Test.pack"),
+            estimate_size("a.pack", 100)
+        );
+        assert_eq!(
+            table.get("Pack/Actor/Compressed.pack"),
+            estimate_size("a.pack", 200)
+        );
+        assert!(!table.contains("Readme.unknown"));
+    }
+}
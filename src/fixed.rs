@@ -0,0 +1,291 @@
+#![cfg_attr(not(feature = "alloc"), allow(clippy::needless_borrow))]
+use core::mem::size_of;
+
+use crate::{
+    bin::{HashEntry, Header, NameEntry, ResTblReader, TableEntry},
+    util::{hash_name, Name},
+    Error, Result, TableIndex,
+};
+
+/// Fixed-capacity, editable RSTB table which needs no allocator, holding up to
+/// `H` hash entries and `N` name entries in sorted arrays. Mirrors the API of
+/// `ResourceSizeTable`, except that insertions fail with
+/// [`Error::CapacityExceeded`] when the relevant array is full.
+#[derive(Debug, Clone)]
+pub struct StaticTable<const H: usize, const N: usize> {
+    crc_table: [HashEntry; H],
+    crc_len: usize,
+    name_table: [NameEntry; N],
+    name_len: usize,
+}
+
+impl<const H: usize, const N: usize> Default for StaticTable<H, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const H: usize, const N: usize> StaticTable<H, N> {
+    /// Construct an empty table
+    pub const fn new() -> Self {
+        Self {
+            crc_table: [HashEntry::new(0, 0); H],
+            crc_len: 0,
+            name_table: [NameEntry::new(Name::EMPTY, 0); N],
+            name_len: 0,
+        }
+    }
+
+    /// Construct a table from a fast readonly parser, failing if it has more
+    /// entries than fit
+    pub fn from_parser(parser: &ResTblReader<'_>) -> Result<Self> {
+        let mut table = Self::new();
+        for entry in parser.iter() {
+            match entry {
+                TableEntry::Hash(entry) => table.set(entry.hash(), entry.value())?,
+                TableEntry::Name(entry) => table.insert_name(&entry.name(), entry.value())?,
+            };
+        }
+        Ok(table)
+    }
+
+    /// Get the total number of hash and name entries in the table
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.crc_len + self.name_len
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    fn hashes(&self) -> &[HashEntry] {
+        &self.crc_table[..self.crc_len]
+    }
+
+    #[inline(always)]
+    fn names(&self) -> &[NameEntry] {
+        &self.name_table[..self.name_len]
+    }
+
+    fn find_hash(&self, hash: u32) -> core::result::Result<usize, usize> {
+        self.hashes().binary_search_by_key(&hash, |e| e.hash())
+    }
+
+    fn find_name(&self, name: &str) -> core::result::Result<usize, usize> {
+        self.names()
+            .binary_search_by(|e| e.name().as_str().cmp(name))
+    }
+
+    /// Check if the specified hash or resource name is present in the table.
+    /// Checks the name table first (if applicable) and then the hash table.
+    pub fn contains<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
+        self.get(needle).is_some()
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name if
+    /// present. Checks the name table first (if applicable) and then the hash
+    /// table.
+    pub fn get<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
+        fn inner<const H: usize, const N: usize>(
+            tbl: &StaticTable<H, N>,
+            needle: TableIndex,
+        ) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => {
+                    tbl.find_hash(hash).ok().map(|i| tbl.crc_table[i].value())
+                }
+                TableIndex::StringIndex(name) => match tbl.find_name(&name) {
+                    Ok(i) => Some(tbl.name_table[i].value()),
+                    Err(_) => tbl
+                        .find_hash(hash_name(&name))
+                        .ok()
+                        .map(|i| tbl.crc_table[i].value()),
+                },
+            }
+        }
+        inner(self, needle.into())
+    }
+
+    /// Set the RSTB value for the specified hash or resource name, returning
+    /// the original value if present. A resource name updates an existing
+    /// name entry, and is otherwise stored as a hash entry. Fails if a new
+    /// entry is needed and the hash table is full.
+    pub fn set<'i, I: Into<TableIndex<'i>>>(&mut self, res: I, value: u32) -> Result<Option<u32>> {
+        fn inner<const H: usize, const N: usize>(
+            tbl: &mut StaticTable<H, N>,
+            needle: TableIndex,
+            value: u32,
+        ) -> Result<Option<u32>> {
+            let hash = match needle {
+                TableIndex::HashIndex(hash) => hash,
+                TableIndex::StringIndex(name) => match tbl.find_name(&name) {
+                    Ok(i) => {
+                        let old = tbl.name_table[i].value();
+                        tbl.name_table[i] = NameEntry::new(tbl.name_table[i].name(), value);
+                        return Ok(Some(old));
+                    }
+                    Err(_) => hash_name(&name),
+                },
+            };
+            match tbl.find_hash(hash) {
+                Ok(i) => {
+                    let old = tbl.crc_table[i].value();
+                    tbl.crc_table[i] = HashEntry::new(hash, value);
+                    Ok(Some(old))
+                }
+                Err(_) if tbl.crc_len == H => Err(Error::CapacityExceeded(H)),
+                Err(i) => {
+                    tbl.crc_table.copy_within(i..tbl.crc_len, i + 1);
+                    tbl.crc_table[i] = HashEntry::new(hash, value);
+                    tbl.crc_len += 1;
+                    Ok(None)
+                }
+            }
+        }
+        inner(self, res.into(), value)
+    }
+
+    /// Set the RSTB value for a resource name in the name table, returning the
+    /// original value if present. The name is cleaned up as by [`Name::new`],
+    /// so this fails with [`Error::NameTooLong`] if it does not fit, or if the
+    /// name is new and the name table is full.
+    pub fn insert_name(&mut self, name: &str, value: u32) -> Result<Option<u32>> {
+        let name = Name::new(name)?;
+        match self.find_name(&name) {
+            Ok(i) => {
+                let old = self.name_table[i].value();
+                self.name_table[i] = NameEntry::new(name, value);
+                Ok(Some(old))
+            }
+            Err(_) if self.name_len == N => Err(Error::CapacityExceeded(N)),
+            Err(i) => {
+                self.name_table.copy_within(i..self.name_len, i + 1);
+                self.name_table[i] = NameEntry::new(name, value);
+                self.name_len += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove the RSTB value for the specified hash or resource name, returning
+    /// the original value if present. Checks the name table first (if
+    /// applicable) and then the hash table.
+    pub fn remove<'i, I: Into<TableIndex<'i>>>(&mut self, res: I) -> Option<u32> {
+        fn inner<const H: usize, const N: usize>(
+            tbl: &mut StaticTable<H, N>,
+            needle: TableIndex,
+        ) -> Option<u32> {
+            let hash = match needle {
+                TableIndex::HashIndex(hash) => hash,
+                TableIndex::StringIndex(name) => match tbl.find_name(&name) {
+                    Ok(i) => {
+                        let old = tbl.name_table[i].value();
+                        tbl.name_table.copy_within(i + 1..tbl.name_len, i);
+                        tbl.name_len -= 1;
+                        return Some(old);
+                    }
+                    Err(_) => hash_name(&name),
+                },
+            };
+            let i = tbl.find_hash(hash).ok()?;
+            let old = tbl.crc_table[i].value();
+            tbl.crc_table.copy_within(i + 1..tbl.crc_len, i);
+            tbl.crc_len -= 1;
+            Some(old)
+        }
+        inner(self, res.into())
+    }
+
+    /// Get the size in bytes of the table in its binary format
+    #[inline(always)]
+    pub fn binary_size(&self) -> usize {
+        Header::FULL_SIZE
+            + self.crc_len * size_of::<HashEntry>()
+            + self.name_len * size_of::<NameEntry>()
+    }
+
+    /// Write the table in its binary format to a buffer, returning the number
+    /// of bytes written
+    pub fn to_binary_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let size = self.binary_size();
+        if buffer.len() < size {
+            return Err(Error::InsufficientBuffer(buffer.len(), size));
+        }
+        Header::with_counts(self.crc_len as u32, self.name_len as u32).write(buffer);
+        let mut pos = Header::FULL_SIZE;
+        for entry in self.hashes() {
+            entry.write(&mut buffer[pos..]);
+            pos += size_of::<HashEntry>();
+        }
+        for entry in self.names() {
+            entry.write(&mut buffer[pos..]);
+            pos += size_of::<NameEntry>();
+        }
+        Ok(pos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StaticTable;
+    use crate::{bin::ResTblReader, test::DATA, Error};
+
+    #[test]
+    fn edit() {
+        let mut table = StaticTable::<4, 2>::new();
+        assert_eq!(table.set(3, 30).unwrap(), None);
+        assert_eq!(table.set(1, 10).unwrap(), None);
+        assert_eq!(table.set("Pack/Actor/Test.pack", 20).unwrap(), None);
+        assert_eq!(table.insert_name("Bake/Test.bkres", 40).unwrap(), None);
+        assert_eq!(table.set("Bake/Test.bkres", 50).unwrap(), Some(40));
+        assert_eq!(table.set(1, 11).unwrap(), Some(10));
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.get("Pack/Actor/Test.pack"), Some(20));
+        assert_eq!(table.remove(3), Some(30));
+        assert!(!table.contains(3));
+
+        let mut buffer = [0u8; 0x200];
+        let len = table.to_binary_into(&mut buffer).unwrap();
+        assert_eq!(len, table.binary_size());
+        let parser = ResTblReader::new(&buffer[..len]).unwrap();
+        assert_eq!(parser.len(), 3);
+        assert_eq!(parser.get(1), Some(11));
+        assert_eq!(parser.get("Pack/Actor/Test.pack"), Some(20));
+        assert_eq!(parser.get("Bake/Test.bkres"), Some(50));
+        assert!(matches!(
+            table.to_binary_into(&mut buffer[..0x10]),
+            Err(Error::InsufficientBuffer(0x10, _))
+        ));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut table = StaticTable::<1, 0>::new();
+        table.set(1, 1).unwrap();
+        assert!(matches!(table.set(2, 2), Err(Error::CapacityExceeded(1))));
+        assert!(matches!(
+            table.insert_name("Test.bgyml", 1),
+            Err(Error::CapacityExceeded(0))
+        ));
+        let parser = ResTblReader::new(DATA).unwrap();
+        assert!(StaticTable::<8, 8>::from_parser(&parser).is_err());
+    }
+
+    #[test]
+    fn long_name() {
+        let mut table = StaticTable::<0, 2>::new();
+        let name = "a".repeat(160);
+        assert!(matches!(
+            table.insert_name(&name, 1),
+            Err(Error::NameTooLong(160))
+        ));
+        assert!(matches!(
+            table.insert_name(&name, 2),
+            Err(Error::NameTooLong(160))
+        ));
+        assert_eq!(table.len(), 0);
+    }
+}
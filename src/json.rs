@@ -0,0 +1,74 @@
+use alloc::{borrow::Cow, collections::BTreeMap, string::String};
+
+use crate::{Name, ResourceSizeTable, Result};
+
+/// The JSON schema used by BOTW-era RSTB tools, with hashes written as
+/// decimal string keys since JSON object keys must be strings
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LegacyJson<'a> {
+    hash_table: Cow<'a, BTreeMap<u32, u32>>,
+    #[serde(borrow)]
+    name_table: BTreeMap<Cow<'a, str>, u32>,
+}
+
+impl ResourceSizeTable {
+    /// Serialize the table to the legacy JSON format read by BOTW-era tools:
+    /// `{"hash_table": {"<hash>": value, ...}, "name_table": {"<name>": value,
+    /// ...}}`
+    pub fn to_legacy_json(&self) -> String {
+        serde_json::to_string(&LegacyJson {
+            hash_table: Cow::Borrowed(&self.crc_table),
+            name_table: self
+                .name_table
+                .iter()
+                .map(|(name, value)| (name.as_str().into(), *value))
+                .collect(),
+        })
+        .expect("Serializing to JSON should never fail")
+    }
+
+    /// Parse a table from the legacy JSON format written by
+    /// [`to_legacy_json`](Self::to_legacy_json). Unlike
+    /// [`from_text`](Self::from_text), names are always kept in the name
+    /// table.
+    pub fn from_legacy_json(json: &str) -> Result<Self> {
+        let legacy: LegacyJson = serde_json::from_str(json)?;
+        Ok(Self {
+            crc_table: legacy.hash_table.into_owned(),
+            name_table: legacy
+                .name_table
+                .into_iter()
+                .map(|(name, value)| Name::new(&name).map(|name| (name, value)))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::DATA, ResourceSizeTable};
+
+    #[test]
+    fn legacy_json() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let json = table.to_legacy_json();
+        assert_eq!(ResourceSizeTable::from_legacy_json(&json).unwrap(), table);
+        let small = ResourceSizeTable::from_legacy_json(
+            r#"{"hash_table": {"1": 2}, "name_table": {"Test/Name.bgyml": 3}}"#,
+        )
+        .unwrap();
+        assert_eq!(small.get(1), Some(2));
+        assert_eq!(small.get_strict("Test/Name.bgyml"), Some(3));
+        let escaped = r#"{"hash_table": {}, "name_table": {"Test\/Escaped.bgyml": 4}}"#;
+        let escaped = ResourceSizeTable::from_legacy_json(escaped).unwrap();
+        assert_eq!(escaped.get_strict("Test/Escaped.bgyml"), Some(4));
+        assert_eq!(
+            small.to_legacy_json(),
+            r#"{"hash_table":{"1":2},"name_table":{"Test/Name.bgyml":3}}"#
+        );
+        assert!(matches!(
+            ResourceSizeTable::from_legacy_json(r#"{"hash_table": {"x": 2}, "name_table": {}}"#),
+            Err(crate::Error::JsonError(_))
+        ));
+    }
+}
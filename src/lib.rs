@@ -5,8 +5,14 @@
 //! - Quick, zero-allocation parser
 //! - Optional `alloc` feature to support editable table which can be serialized to
 //!   binary or (with the `yaml` feature) YAML.
-//! - `no_std` support (optional `std` feature)
+//! - `no_std` support (optional `std` feature), including a fixed-capacity
+//!   editable table which needs no allocator
 //! - optional Serde support (`serde` feature)
+//! - optional conversion to the legacy BOTW JSON format (`json` feature)
+//! - optional reading and writing of zstd compressed tables (`zstd` feature)
+//! - optional `log` integration for parsing and mutations (`log` feature)
+//! - optional parallel serialization and bulk edits for very large tables
+//!   (`rayon` feature)
 //! - `aarch64-nintendo-switch-freestanding` support (without the `std` feature)
 //!
 //! ## Example Usage
@@ -55,7 +61,7 @@
 //! passing feature flags, you will need to run the full command yourself, as
 //! follows:
 //!
-//! ```
+//! ```sh
 //! cargo build -Z build-std=core,compiler_builtins,alloc --target aarch64-nintendo-switch-freestanding --no-default-features
 //! ```
 //!
@@ -66,7 +72,82 @@
 #![cfg_attr(not(any(feature = "std", test)), no_std)]
 #[cfg(feature = "alloc")]
 extern crate alloc;
+
+/// Emits a `log::trace!` line when the `log` feature is enabled, and nothing
+/// otherwise
+#[allow(unused_macros)]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+/// Emits a `log::debug!` line when the `log` feature is enabled, and nothing
+/// otherwise
+#[allow(unused_macros)]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+/// Compute the hash of a resource path as stored in the RSTB hash table. This
+/// is a `const fn`, so hashes of well-known paths can be computed at compile
+/// time and used as constants or match patterns.
+///
+/// ```
+/// const COOKING_TABLE: u32 = restbl::hash("Cooking/CookingTable.game__cooking__Table.bgyml");
+/// assert_eq!(restbl::hash!("Cooking/CookingTable.game__cooking__Table.bgyml"), COOKING_TABLE);
+/// ```
+#[inline]
+pub const fn hash(path: &str) -> u32 {
+    util::hash_name(path)
+}
+
+/// Compute the hash of a resource path at compile time. The path must be a
+/// constant expression; see [`hash()`].
+#[macro_export]
+macro_rules! hash {
+    ($path:expr) => {{
+        const HASH: u32 = $crate::hash($path);
+        HASH
+    }};
+}
+
 pub mod bin;
+#[cfg(feature = "alloc")]
+mod borrowed;
+#[cfg(feature = "zstd")]
+mod compress;
+#[cfg(feature = "alloc")]
+mod dict;
+#[cfg(feature = "alloc")]
+mod diff;
+pub mod estimate;
+mod fixed;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "alloc")]
+mod lint;
+#[cfg(feature = "alloc")]
+mod ordered;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod path;
+#[cfg(feature = "alloc")]
+mod summary;
+#[cfg(all(feature = "alloc", any(test, feature = "testutil")))]
+pub mod testutil;
 #[cfg(feature = "yaml")]
 mod text;
 mod util;
@@ -75,11 +156,27 @@ mod util;
 use alloc::{
     borrow::{Cow, ToOwned},
     collections::BTreeMap,
+    string::ToString,
 };
 #[cfg(not(feature = "alloc"))]
 pub use bin::ResTblReader;
+#[cfg(feature = "alloc")]
+pub use borrowed::BorrowedTable;
+#[cfg(feature = "zstd")]
+pub use compress::recompress;
+#[cfg(feature = "alloc")]
+pub use dict::{NameDict, ResolvedKey};
+#[cfg(feature = "alloc")]
+pub use diff::{DiffStats, RstbDiff};
+pub use fixed::StaticTable;
+#[cfg(feature = "alloc")]
+pub use lint::{LintFinding, LintReport};
+#[cfg(feature = "alloc")]
+pub use ordered::OrderedTable;
+#[cfg(feature = "alloc")]
+pub use summary::{TableSummary, ValueStats};
 use thiserror_no_std::Error;
-use util::Name;
+pub use util::{hash_name_with, HashAlgo, Name};
 
 /// Result type for this create
 pub type Result<T> = core::result::Result<T, Error>;
@@ -91,26 +188,60 @@ pub enum Error {
     InsufficientData(usize, &'static str),
     #[error("Invalid magic: {0:?}, expected \"RESTBL\"")]
     InvalidMagic([u8; 6]),
+    #[error("Input appears to be zstd compressed; decompress it first, e.g. with from_compressed")]
+    Compressed,
+    #[error("Unsupported RSTB header version {0} with string block size {1}")]
+    UnsupportedHeader(u32, u32),
     #[error("Invalid table size: {0}, expected {1}")]
     InvalidTableSize(usize, usize),
     #[error(transparent)]
     Utf8Error(#[from] core::str::Utf8Error),
     #[error("Buffer too small for output: found {0} bytes, requires at least {1}")]
     InsufficientBuffer(usize, usize),
+    #[error("Table entries are not in sorted order")]
+    Unsorted,
+    #[error("Table capacity exceeded: room for only {0} entries")]
+    CapacityExceeded(usize),
+    #[error("Unexpected non-zero data after the end of the table: {0} bytes")]
+    TrailingData(usize),
+    #[error("Invalid UTF-8 in name entry {0}: {1}")]
+    InvalidName(usize, core::str::Utf8Error),
+    #[error("Resource name is {0} bytes long, but at most 159 bytes fit")]
+    NameTooLong(usize),
+    #[error("Resource name contains a NUL byte at offset {0}")]
+    NulInName(usize),
+    #[error("Table has {0} entries, more than the limit of {1}")]
+    TooManyEntries(usize, usize),
+    #[error("Header claims {0} entries, but {1} could be read")]
+    EntryCountMismatch(usize, usize),
+    #[error("Duplicate hash table entry for {0}")]
+    DuplicateHash(u32),
+    #[cfg(feature = "alloc")]
+    #[error("Duplicate name table entry for {0}")]
+    DuplicateName(alloc::string::String),
+    #[cfg(feature = "alloc")]
+    #[error("Name table entry {0} is overlapped by a hash table entry")]
+    Overlap(alloc::string::String),
+    #[cfg(feature = "alloc")]
+    #[error("An entry already exists for {0}")]
+    KeyExists(alloc::string::String),
     #[cfg(feature = "std")]
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
     #[cfg(all(feature = "alloc", feature = "yaml"))]
-    #[error("Invalid YAML line: {0}")]
-    YamlError(alloc::string::String),
+    #[error("Invalid YAML on line {0}: {1}")]
+    YamlError(usize, alloc::string::String),
     #[cfg(feature = "yaml")]
-    #[error("Invalid number in YAML line: {0}")]
-    YamlInvalidNumber(#[from] core::num::ParseIntError),
+    #[error("Invalid number in YAML on line {0}: {1}")]
+    YamlInvalidNumber(usize, core::num::ParseIntError),
 }
 
 /// Represents an index into the RSTB, which can be a canonical resource path or
 /// its hash
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TableIndex<'a> {
     HashIndex(u32),
     #[cfg(feature = "alloc")]
@@ -119,6 +250,59 @@ pub enum TableIndex<'a> {
     StringIndex(&'a str),
 }
 
+impl TableIndex<'_> {
+    /// Returns the resource name if this is a name index
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TableIndex::HashIndex(_) => None,
+            TableIndex::StringIndex(name) => Some(&**name),
+        }
+    }
+
+    /// Returns the hash if this is a hash index, without hashing names
+    #[inline]
+    pub fn as_hash(&self) -> Option<u32> {
+        match self {
+            TableIndex::HashIndex(hash) => Some(*hash),
+            TableIndex::StringIndex(_) => None,
+        }
+    }
+}
+
+/// Formats the index as it appears in the YAML text format: the hash in
+/// decimal or the resource name, quoted if it would be ambiguous
+impl core::fmt::Display for TableIndex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TableIndex::HashIndex(hash) => hash.fmt(f),
+            TableIndex::StringIndex(name) => TextKey(name).fmt(f),
+        }
+    }
+}
+
+/// A resource name as written in YAML, wrapped in double quotes if it would
+/// otherwise be read back as a hash or a comment, or would lose its own quotes
+struct TextKey<'a>(&'a str);
+
+impl TextKey<'_> {
+    fn needs_quotes(name: &str) -> bool {
+        name.parse::<u32>().is_ok()
+            || name.starts_with('#')
+            || (name.len() >= 2 && name.starts_with('"') && name.ends_with('"'))
+    }
+}
+
+impl core::fmt::Display for TextKey<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if Self::needs_quotes(self.0) {
+            write!(f, "\"{}\"", self.0)
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
+
 impl From<u32> for TableIndex<'_> {
     fn from(value: u32) -> Self {
         TableIndex::HashIndex(value)
@@ -165,6 +349,154 @@ impl From<alloc::string::String> for TableIndex<'_> {
     }
 }
 
+/// An owned key for an RSTB entry, either a hash or a resource name. Unlike
+/// [`TableIndex`], this has no lifetime and implements `Hash`, so it can be
+/// stored in external collections.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OwnedKey {
+    Hash(u32),
+    Name(Name),
+}
+
+impl OwnedKey {
+    /// Returns the hash of the key, hashing the resource name if necessary
+    pub fn to_hash(&self) -> u32 {
+        match self {
+            OwnedKey::Hash(hash) => *hash,
+            OwnedKey::Name(name) => util::hash_name(name),
+        }
+    }
+}
+
+/// Formats the key as it appears in the YAML text format: the hash in decimal
+/// or the resource name, quoted if it would be ambiguous
+impl core::fmt::Display for OwnedKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OwnedKey::Hash(hash) => hash.fmt(f),
+            OwnedKey::Name(name) => TextKey(name).fmt(f),
+        }
+    }
+}
+
+impl From<bin::TableEntry> for OwnedKey {
+    fn from(value: bin::TableEntry) -> Self {
+        match value {
+            bin::TableEntry::Hash(entry) => OwnedKey::Hash(entry.hash()),
+            bin::TableEntry::Name(entry) => OwnedKey::Name(entry.name()),
+        }
+    }
+}
+
+impl From<&str> for OwnedKey {
+    fn from(value: &str) -> Self {
+        OwnedKey::Name(value.into())
+    }
+}
+
+impl From<&TableIndex<'_>> for OwnedKey {
+    fn from(value: &TableIndex<'_>) -> Self {
+        match value {
+            TableIndex::HashIndex(hash) => OwnedKey::Hash(*hash),
+            TableIndex::StringIndex(name) => OwnedKey::Name(Name::from(&**name)),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedKey> for TableIndex<'a> {
+    fn from(value: &'a OwnedKey) -> Self {
+        match value {
+            OwnedKey::Hash(hash) => TableIndex::HashIndex(*hash),
+            OwnedKey::Name(name) => name.into(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<std::collections::HashMap<alloc::string::String, u32>> for ResourceSizeTable {
+    type Error = Error;
+
+    /// Build a table from resource paths and values, setting each as by
+    /// [`ResourceSizeTable::set`] after cleaning it up with [`Name::new`].
+    /// Fails if any path is not a valid resource name.
+    fn try_from(
+        value: std::collections::HashMap<alloc::string::String, u32>,
+    ) -> core::result::Result<Self, Self::Error> {
+        let mut table = Self::new();
+        for (path, size) in value {
+            table.set(Name::new(&path)?.as_str(), size);
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<BTreeMap<u32, u32>> for ResourceSizeTable {
+    /// Build a table with the given hash entries and no name entries
+    fn from(crc_table: BTreeMap<u32, u32>) -> Self {
+        Self {
+            crc_table,
+            name_table: BTreeMap::new(),
+        }
+    }
+}
+
+/// Which of the two RSTB tables an entry is stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// The hash table, keyed by the CRC32 of the resource name
+    Hash,
+    /// The name table, keyed by the full resource name
+    Name,
+}
+
+/// Which entry wins when a resource name matches both a name entry and a hash
+/// entry, as for [`ResourceSizeTable::get_with_precedence`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precedence {
+    /// Prefer the name entry, as [`ResourceSizeTable::get`] does
+    #[default]
+    NameFirst,
+    /// Prefer the hash entry
+    HashFirst,
+}
+
+/// Whether a resource has an entry in a table, as returned by
+/// [`ResourceSizeTable::status`]. Unlike `Option<u32>`, this keeps an absent
+/// entry visibly distinct from one present with a value of zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryStatus {
+    /// The table has no entry for the resource
+    Absent,
+    /// The table has an entry for the resource with this value, which may be
+    /// zero
+    Present(u32),
+}
+
+impl EntryStatus {
+    /// Returns the value if the entry is present
+    #[inline(always)]
+    pub fn value(self) -> Option<u32> {
+        match self {
+            EntryStatus::Absent => None,
+            EntryStatus::Present(value) => Some(value),
+        }
+    }
+
+    /// Check if the entry is present with a value of zero
+    #[inline(always)]
+    pub fn is_zero(self) -> bool {
+        self == EntryStatus::Present(0)
+    }
+}
+
+impl From<Option<u32>> for EntryStatus {
+    fn from(value: Option<u32>) -> Self {
+        value.map_or(EntryStatus::Absent, EntryStatus::Present)
+    }
+}
+
 /// Data structure representing Tears of the Kingdom's resource size table
 /// (`ResourceSizeTable.Product.rsizetable.zs`). Requires the `alloc` feature.
 /// Can be serialized or deserialized to binary or (with the `text` feature) a
@@ -184,6 +516,71 @@ impl ResourceSizeTable {
         Self::default()
     }
 
+    /// Construct a table from existing hash and name maps, failing with
+    /// [`Error::Overlap`] if any name's hash also has an entry in the hash
+    /// map. Prefer this to assigning the public fields directly, which skips
+    /// the check.
+    pub fn from_maps(
+        crc_table: BTreeMap<u32, u32>,
+        name_table: BTreeMap<Name, u32>,
+    ) -> Result<Self> {
+        if let Some(name) = name_table
+            .keys()
+            .find(|name| crc_table.contains_key(&util::hash_name(name)))
+        {
+            return Err(Error::Overlap(name.to_string()));
+        }
+        Ok(Self {
+            crc_table,
+            name_table,
+        })
+    }
+
+    /// Construct a table with the given hash entries and no name entries, for
+    /// workflows which only ever deal in hashes. Equivalent to the `From`
+    /// impl, but easier to find.
+    pub fn from_crc_map(crc_table: BTreeMap<u32, u32>) -> Self {
+        crc_table.into()
+    }
+
+    /// Consume the table, returning only its hash entries. Each name entry is
+    /// folded in under its hash, replacing any hash entry for the same
+    /// resource, as name entries take precedence in [`get`](Self::get). For a
+    /// table with no name entries this simply returns the hash map.
+    pub fn crc_only(self) -> BTreeMap<u32, u32> {
+        let mut crc_table = self.crc_table;
+        for (name, value) in self.name_table {
+            crc_table.insert(util::hash_name(&name), value);
+        }
+        crc_table
+    }
+
+    /// Check the invariants which the public fields cannot enforce on their
+    /// own, as after deserializing a table with serde, which fills each map
+    /// independently. Callers deserializing untrusted data should call this
+    /// before using the table.
+    ///
+    /// A name whose hash also has an entry in the hash map is an overlap. With
+    /// `repair`, overlaps are fixed as by [`canonicalize`](Self::canonicalize);
+    /// otherwise the first fails with [`Error::Overlap`]. A name filling the
+    /// whole 160-byte field, leaving no room for its NUL terminator, cannot
+    /// be repaired and fails with [`Error::NameTooLong`].
+    pub fn ensure_invariants(&mut self, repair: bool) -> Result<()> {
+        if let Some(name) = self.name_table.keys().find(|name| name.len() >= 160) {
+            return Err(Error::NameTooLong(name.len()));
+        }
+        if repair {
+            self.canonicalize();
+        } else if let Some(name) = self
+            .name_table
+            .keys()
+            .find(|name| self.crc_table.contains_key(&util::hash_name(name)))
+        {
+            return Err(Error::Overlap(name.to_string()));
+        }
+        Ok(())
+    }
+
     /// Construct an owned table from a fast readonly parser
     pub fn from_parser(parser: &bin::ResTblReader<'_>) -> Self {
         let mut crc_table = BTreeMap::new();
@@ -200,6 +597,32 @@ impl ResourceSizeTable {
         }
     }
 
+    /// Construct an owned table from a fast readonly parser, failing with
+    /// [`Error::Unsorted`] if the parser's hash or name entries are not in
+    /// strictly ascending order. Tables built from such files are re-sorted,
+    /// so they would not serialize back to the same bytes.
+    pub fn from_parser_validated(parser: &bin::ResTblReader<'_>) -> Result<Self> {
+        let mut last_hash = None;
+        let mut last_name: Option<Name> = None;
+        for entry in parser.iter() {
+            match entry {
+                bin::TableEntry::Hash(entry) => {
+                    if last_hash.is_some_and(|last| last >= entry.hash()) {
+                        return Err(Error::Unsorted);
+                    }
+                    last_hash = Some(entry.hash());
+                }
+                bin::TableEntry::Name(entry) => {
+                    if last_name.is_some_and(|last| last >= entry.name()) {
+                        return Err(Error::Unsorted);
+                    }
+                    last_name = Some(entry.name());
+                }
+            }
+        }
+        Ok(Self::from_parser(parser))
+    }
+
     /// Get the total number of hash and name entries in the table
     #[inline(always)]
     pub fn len(&self) -> usize {
@@ -213,6 +636,10 @@ impl ResourceSizeTable {
 
     /// Check if the specified hash or resource name is present in the table.
     /// Checks the name table first (if applicable) and then the hash table.
+    ///
+    /// A resource name missing from the name table falls back to a lookup of
+    /// its hash in the hash table. Use [`ResourceSizeTable::contains_strict`]
+    /// to disable the fallback.
     pub fn contains<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
         fn inner(tbl: &ResourceSizeTable, needle: TableIndex) -> bool {
             match needle {
@@ -231,6 +658,17 @@ impl ResourceSizeTable {
     /// Returns the RSTB value for the specified hash or resource name if
     /// present. Checks the name table first (if applicable) and then the hash
     /// table.
+    ///
+    /// A resource name missing from the name table falls back to a lookup of
+    /// its hash in the hash table. Use [`ResourceSizeTable::get_strict`] to
+    /// disable the fallback.
+    ///
+    /// If a resource name matches both a name entry and a hash entry with
+    /// different values, the name entry's value is returned. The game's own
+    /// tables never contain such collisions, but edited ones may. This
+    /// precedence is guaranteed, and shared by the readonly parser and the
+    /// owned table. Use [`get_with_precedence`](Self::get_with_precedence) to
+    /// prefer the hash entry instead.
     pub fn get<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
         fn inner(tbl: &ResourceSizeTable, needle: TableIndex) -> Option<u32> {
             match needle {
@@ -247,6 +685,102 @@ impl ResourceSizeTable {
         inner(self, needle.into())
     }
 
+    /// Returns the RSTB value for the specified hash or resource name, or
+    /// `default` if it is not present. Resolves the needle the same way as
+    /// [`get`](Self::get).
+    pub fn get_or<'i, I: Into<TableIndex<'i>>>(&self, needle: I, default: u32) -> u32 {
+        self.get(needle).unwrap_or(default)
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name,
+    /// resolving a collision between a name entry and a hash entry for the
+    /// same resource name with the given precedence. Otherwise this is the
+    /// same as [`get`](Self::get), which uses [`Precedence::NameFirst`]. A
+    /// hash is only ever looked up in the hash table.
+    pub fn get_with_precedence<'i, I: Into<TableIndex<'i>>>(
+        &self,
+        needle: I,
+        precedence: Precedence,
+    ) -> Option<u32> {
+        fn inner(
+            tbl: &ResourceSizeTable,
+            needle: TableIndex,
+            precedence: Precedence,
+        ) -> Option<u32> {
+            match (needle, precedence) {
+                (TableIndex::StringIndex(name), Precedence::HashFirst) => tbl
+                    .get_strict(util::hash_name(&name))
+                    .or_else(|| tbl.get_strict(TableIndex::StringIndex(name))),
+                (needle, _) => tbl.get(needle),
+            }
+        }
+        inner(self, needle.into(), precedence)
+    }
+
+    /// Returns whether the specified hash or resource name has an entry, and
+    /// its value if so. Resolves the needle the same way as
+    /// [`get`](Self::get).
+    pub fn status<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> EntryStatus {
+        self.get(needle).into()
+    }
+
+    /// Returns the RSTB value for a resource path after converting it with
+    /// [`path::canonicalize_path`], so that paths like
+    /// `.\Pack\Actor\Test.pack.zs` find the entry for `Pack/Actor/Test.pack`
+    pub fn get_canonical(&self, path: &str) -> Option<u32> {
+        self.get(path::canonicalize_path(path).as_ref())
+    }
+
+    /// Set the RSTB value for a resource path after converting it with
+    /// [`path::canonicalize_path`], returning the original value if present
+    pub fn set_canonical(&mut self, path: &str, value: u32) -> Option<u32> {
+        self.set(path::canonicalize_path(path).as_ref(), value)
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name if
+    /// present, along with the table it was found in. Resolves the needle the
+    /// same way as [`get`](Self::get), so a resource name missing from the name
+    /// table is reported as found in the hash table if its hash is present.
+    pub fn get_located<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<(u32, EntryKind)> {
+        fn inner(tbl: &ResourceSizeTable, needle: TableIndex) -> Option<(u32, EntryKind)> {
+            match needle {
+                TableIndex::HashIndex(hash) => {
+                    tbl.crc_table.get(&hash).map(|v| (*v, EntryKind::Hash))
+                }
+                TableIndex::StringIndex(name) => tbl
+                    .name_table
+                    .get(&Name::from(name.as_ref()))
+                    .map(|v| (*v, EntryKind::Name))
+                    .or_else(|| {
+                        let hash = util::hash_name(&name);
+                        tbl.crc_table.get(&hash).map(|v| (*v, EntryKind::Hash))
+                    }),
+            }
+        }
+        inner(self, needle.into())
+    }
+
+    /// Check if the specified hash or resource name is present in the table,
+    /// without falling back. A hash is only looked up in the hash table and a
+    /// resource name is only looked up in the name table.
+    pub fn contains_strict<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> bool {
+        self.get_strict(needle).is_some()
+    }
+
+    /// Returns the RSTB value for the specified hash or resource name if
+    /// present, without falling back. A hash is only looked up in the hash
+    /// table and a resource name is only looked up in the name table.
+    pub fn get_strict<'i, I: Into<TableIndex<'i>>>(&self, needle: I) -> Option<u32> {
+        fn inner(tbl: &ResourceSizeTable, needle: TableIndex) -> Option<u32> {
+            match needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.get(&hash),
+                TableIndex::StringIndex(name) => tbl.name_table.get(&Name::from(name.as_ref())),
+            }
+            .copied()
+        }
+        inner(self, needle.into())
+    }
+
     /// Returns a mutable reference to the RSTB value for the specified hash or
     /// resource name if present. Checks the name table first (if applicable)
     /// and then the hash table.
@@ -274,20 +808,22 @@ impl ResourceSizeTable {
     /// applicable) and then the hash table.
     pub fn set<'i, I: Into<TableIndex<'i>>>(&mut self, res: I, value: u32) -> Option<u32> {
         fn inner(tbl: &mut ResourceSizeTable, needle: TableIndex, value: u32) -> Option<u32> {
-            match needle {
-                TableIndex::HashIndex(hash) => tbl.crc_table.insert(hash, value),
+            let old = match &needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.insert(*hash, value),
                 TableIndex::StringIndex(name) => {
                     match tbl.name_table.entry(Name::from(name.as_ref())) {
                         alloc::collections::btree_map::Entry::Occupied(mut e) => {
                             Some(e.insert(value))
                         }
                         alloc::collections::btree_map::Entry::Vacant(_) => {
-                            let hash = util::hash_name(&name);
+                            let hash = util::hash_name(name);
                             tbl.crc_table.insert(hash, value)
                         }
                     }
                 }
-            }
+            };
+            trace!("Set RSTB entry {:?}: {:?} -> {}", needle, old, value);
+            old
         }
         inner(self, res.into(), value)
     }
@@ -297,20 +833,347 @@ impl ResourceSizeTable {
     /// applicable) and then the hash table.
     pub fn remove<'i, I: Into<TableIndex<'i>>>(&mut self, res: I) -> Option<u32> {
         fn inner(tbl: &mut ResourceSizeTable, needle: TableIndex<'_>) -> Option<u32> {
-            match needle {
-                TableIndex::HashIndex(hash) => tbl.crc_table.remove(&hash),
+            let old = match &needle {
+                TableIndex::HashIndex(hash) => tbl.crc_table.remove(hash),
                 TableIndex::StringIndex(name) => tbl
                     .name_table
                     .remove(&Name::from(name.as_ref()))
                     .or_else(|| {
-                        let hash = util::hash_name(&name);
+                        let hash = util::hash_name(name);
                         tbl.crc_table.remove(&hash)
                     }),
-            }
+            };
+            trace!("Removed RSTB entry {:?}: {:?}", needle, old);
+            old
         }
         inner(self, res.into())
     }
 
+    /// Move the value stored for one hash or resource name to another,
+    /// returning whether the old key was present. The new key is inserted
+    /// following the same rules as [`ResourceSizeTable::set`]. Fails with
+    /// [`Error::KeyExists`] without modifying the table if the new key is
+    /// already present.
+    pub fn rename<'o, 'n, O: Into<TableIndex<'o>>, N: Into<TableIndex<'n>>>(
+        &mut self,
+        old: O,
+        new: N,
+    ) -> Result<bool> {
+        fn inner(tbl: &mut ResourceSizeTable, old: TableIndex, new: TableIndex) -> Result<bool> {
+            if tbl.contains(new.clone()) {
+                return Err(Error::KeyExists(OwnedKey::from(&new).to_string()));
+            }
+            match tbl.remove(old) {
+                Some(value) => {
+                    tbl.set(new, value);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+        inner(self, old.into(), new.into())
+    }
+
+    /// Flatten the table into sorted `(hash, value)` pairs, hashing every name
+    /// entry. Where a name's hash matches a hash entry, the name entry's value
+    /// wins, matching [`ResourceSizeTable::get`]. Where several names share a
+    /// hash, the first name in sorted order wins.
+    pub fn to_hash_vec(&self) -> alloc::vec::Vec<(u32, u32)> {
+        let mut flat = self.crc_table.clone();
+        let mut seen = alloc::collections::BTreeSet::new();
+        for (name, value) in &self.name_table {
+            let hash = util::hash_name(name);
+            if seen.insert(hash) {
+                flat.insert(hash, *value);
+            }
+        }
+        flat.into_iter().collect()
+    }
+
+    /// Split the table in two, consuming it. Entries for which the predicate
+    /// returns `true` go into the first table and the rest into the second.
+    /// Hash and name entries stay in the same kind of table they came from.
+    pub fn partition(
+        self,
+        mut pred: impl FnMut(TableIndex<'_>, u32) -> bool,
+    ) -> (ResourceSizeTable, ResourceSizeTable) {
+        let (crc_a, crc_b) = self
+            .crc_table
+            .into_iter()
+            .partition(|(hash, value)| pred(TableIndex::HashIndex(*hash), *value));
+        let (name_a, name_b) = self
+            .name_table
+            .into_iter()
+            .partition(|(name, value)| pred(name.into(), *value));
+        (
+            ResourceSizeTable {
+                crc_table: crc_a,
+                name_table: name_a,
+            },
+            ResourceSizeTable {
+                crc_table: crc_b,
+                name_table: name_b,
+            },
+        )
+    }
+
+    /// Merge the entries of another table into this one, overwriting any
+    /// existing values. Entries are matched whether they are stored by name
+    /// or by hash: a merged name entry replaces a hash entry for its hash, and
+    /// a merged hash entry overwrites the name entry with that hash, unless
+    /// several names share the hash, in which case it is kept separate.
+    pub fn merge(&mut self, other: &ResourceSizeTable) {
+        let mut name_hashes = BTreeMap::new();
+        for name in self.name_table.keys() {
+            add_name_hash(&mut name_hashes, util::hash_name(name), *name);
+        }
+        for (name, value) in &other.name_table {
+            let old = match self.name_table.get_mut(name) {
+                Some(old) => Some(core::mem::replace(old, *value)),
+                None => {
+                    let hash = util::hash_name(name);
+                    add_name_hash(&mut name_hashes, hash, *name);
+                    self.name_table.insert(*name, *value);
+                    self.crc_table.remove(&hash)
+                }
+            };
+            trace!("Merged RSTB name entry {}: {:?} -> {}", name, old, value);
+        }
+        for (hash, value) in &other.crc_table {
+            let old = match (self.crc_table.get_mut(hash), name_hashes.get(hash)) {
+                (Some(old), _) => Some(core::mem::replace(old, *value)),
+                (None, Some(Some(name))) => self.name_table.insert(*name, *value),
+                (None, _) => self.crc_table.insert(*hash, *value),
+            };
+            trace!("Merged RSTB hash entry {}: {:?} -> {}", hash, old, value);
+        }
+    }
+
+    /// Merge a stack of tables in one pass, combining the values of entries
+    /// present in more than one table with `reducer`, which is given the
+    /// value merged so far and the next table's value. For example,
+    /// `u32::max` keeps the largest value for each resource. The first table
+    /// is kept as is, and an empty iterator gives an empty table.
+    ///
+    /// Entries are matched across tables whether they are stored by name or
+    /// by hash. A name entry absorbs a merged hash entry for its hash, and a
+    /// hash entry is merged into a name entry with that hash, unless several
+    /// merged names share the hash, in which case it is kept separate.
+    pub fn merge_all(
+        tables: impl IntoIterator<Item = ResourceSizeTable>,
+        reducer: impl Fn(u32, u32) -> u32,
+    ) -> ResourceSizeTable {
+        let mut tables = tables.into_iter();
+        let Some(mut merged) = tables.next() else {
+            return Self::new();
+        };
+        let mut name_hashes = BTreeMap::new();
+        for name in merged.name_table.keys() {
+            add_name_hash(&mut name_hashes, util::hash_name(name), *name);
+        }
+        for table in tables {
+            for (name, value) in table.name_table {
+                if let Some(old) = merged.name_table.get_mut(&name) {
+                    *old = reducer(*old, value);
+                    continue;
+                }
+                let hash = util::hash_name(&name);
+                let value = match merged.crc_table.remove(&hash) {
+                    Some(old) => reducer(old, value),
+                    None => value,
+                };
+                merged.name_table.insert(name, value);
+                add_name_hash(&mut name_hashes, hash, name);
+            }
+            for (hash, value) in table.crc_table {
+                let old = match (merged.crc_table.get_mut(&hash), name_hashes.get(&hash)) {
+                    (Some(old), _) => old,
+                    (None, Some(Some(name))) => merged
+                        .name_table
+                        .get_mut(name)
+                        .expect("Indexed names must be in the name table"),
+                    (None, _) => {
+                        merged.crc_table.insert(hash, value);
+                        continue;
+                    }
+                };
+                *old = reducer(*old, value);
+            }
+        }
+        merged
+    }
+
+    /// Iterate all RSTB entries, hash table first and then name table, in the
+    /// same order they are serialized
+    pub fn iter(&self) -> impl Iterator<Item = (TableIndex<'_>, u32)> {
+        self.crc_table
+            .iter()
+            .map(|(hash, value)| (TableIndex::from(*hash), *value))
+            .chain(
+                self.name_table
+                    .iter()
+                    .map(|(name, value)| (TableIndex::from(name), *value)),
+            )
+    }
+
+    /// Iterate entries with a value of zero or below `min`, which usually
+    /// indicate resources the game will fail to load
+    pub fn find_suspicious(&self, min: u32) -> impl Iterator<Item = (TableIndex<'_>, u32)> {
+        self.iter()
+            .filter(move |(_, value)| *value == 0 || *value < min)
+    }
+
+    /// Remove every hash entry whose hash equals the hash of a name entry,
+    /// returning the number of entries removed. Name entries take precedence,
+    /// matching the lookup order of [`ResourceSizeTable::get`], so the
+    /// serialized table has a single unambiguous entry for each resource.
+    ///
+    /// This is never applied implicitly. The game's own tables never contain
+    /// such overlaps, so they are unaffected.
+    pub fn canonicalize(&mut self) -> usize {
+        let before = self.crc_table.len();
+        for name in self.name_table.keys() {
+            self.crc_table.remove(&util::hash_name(name));
+        }
+        before - self.crc_table.len()
+    }
+
+    /// Get the number of hash entries whose hash equals the hash of a name
+    /// entry, which is the number [`canonicalize`](Self::canonicalize) would
+    /// remove
+    pub fn overlapping_count(&self) -> usize {
+        self.name_table
+            .keys()
+            .map(|name| util::hash_name(name))
+            .collect::<alloc::collections::BTreeSet<_>>()
+            .into_iter()
+            .filter(|hash| self.crc_table.contains_key(hash))
+            .count()
+    }
+
+    /// Check if the table is in canonical minimal form, as a mod's table
+    /// should be before distribution. That means both:
+    ///
+    /// - No overlaps: [`overlapping_count`](Self::overlapping_count) is 0, so
+    ///   [`canonicalize`](Self::canonicalize) would change nothing
+    /// - No demotable names: [`demotion_savings`](Self::demotion_savings) is
+    ///   0, so every name entry is needed to disambiguate a hash collision
+    ///
+    /// Entry order is not checked, since the owned table always serializes
+    /// its entries sorted.
+    pub fn is_canonical(&self) -> bool {
+        self.overlapping_count() == 0 && self.demotion_savings() == 0
+    }
+
+    /// Get the number of bytes the serialized table would shrink by if every
+    /// name entry which can be stored by its hash alone were moved to the hash
+    /// table. A name can be demoted only if no hash entry and no other name
+    /// entry shares its hash; otherwise demoting it would lose or shadow a
+    /// value.
+    pub fn demotion_savings(&self) -> usize {
+        let mut hash_counts = BTreeMap::<u32, usize>::new();
+        for name in self.name_table.keys() {
+            *hash_counts.entry(util::hash_name(name)).or_default() += 1;
+        }
+        let demotable = hash_counts
+            .into_iter()
+            .filter(|(hash, count)| *count == 1 && !self.crc_table.contains_key(hash))
+            .count();
+        demotable
+            * (core::mem::size_of::<bin::NameEntry>() - core::mem::size_of::<bin::HashEntry>())
+    }
+
+    /// Compute a fingerprint of the table's contents, stable across runs and
+    /// platforms and independent of the order entries were inserted in.
+    ///
+    /// The hash is 64-bit FNV-1a (offset basis `0xcbf29ce484222325`, prime
+    /// `0x100000001b3`) over the following bytes, with all integers
+    /// little-endian `u32`s:
+    ///
+    /// 1. The number of hash entries, then each hash entry in ascending order
+    ///    of hash as its hash followed by its value
+    /// 2. The number of name entries, then each name entry in ascending
+    ///    byte order of name as the UTF-8 name, a NUL byte, and its value
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+        fn feed(hash: u64, bytes: &[u8]) -> u64 {
+            bytes
+                .iter()
+                .fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME))
+        }
+        let mut hash = feed(OFFSET_BASIS, &(self.crc_table.len() as u32).to_le_bytes());
+        for (crc, value) in &self.crc_table {
+            hash = feed(hash, &crc.to_le_bytes());
+            hash = feed(hash, &value.to_le_bytes());
+        }
+        hash = feed(hash, &(self.name_table.len() as u32).to_le_bytes());
+        for (name, value) in &self.name_table {
+            hash = feed(hash, name.as_bytes());
+            hash = feed(hash, &[0]);
+            hash = feed(hash, &value.to_le_bytes());
+        }
+        hash
+    }
+
+    /// Estimate the heap memory used by the table, in bytes. This is only an
+    /// approximation: it assumes the standard library's B-tree layout of up to
+    /// 11 entries per node, with nodes about two-thirds full, and ignores
+    /// allocator overhead.
+    pub fn memory_footprint(&self) -> usize {
+        use core::mem::size_of;
+        fn btree_footprint<K, V>(len: usize) -> usize {
+            const CAPACITY: usize = 11;
+            // Parent pointer, index in parent, and length
+            const NODE_HEADER: usize = 16;
+            let node_size = NODE_HEADER + CAPACITY * (size_of::<K>() + size_of::<V>());
+            len.div_ceil(CAPACITY * 2 / 3) * node_size
+        }
+        btree_footprint::<u32, u32>(self.crc_table.len())
+            + btree_footprint::<Name, u32>(self.name_table.len())
+    }
+
+    /// Iterate the resource names in the name table, in sorted order
+    pub fn name_paths(&self) -> impl Iterator<Item = &str> + '_ {
+        self.name_table.keys().map(|name| name.as_str())
+    }
+
+    /// Iterate the hash entries whose hashes fall within a range, in
+    /// ascending order
+    pub fn hashes_in_range(
+        &self,
+        range: core::ops::Range<u32>,
+    ) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.crc_table
+            .range(range)
+            .map(|(hash, value)| (*hash, *value))
+    }
+
+    /// Multiply every value in both tables by `factor`, rounding down. Results
+    /// too large for a `u32` saturate at `u32::MAX`, and negative or NaN
+    /// factors give 0.
+    pub fn scale_all(&mut self, factor: f32) {
+        for value in self
+            .crc_table
+            .values_mut()
+            .chain(self.name_table.values_mut())
+        {
+            *value = (*value as f64 * factor as f64) as u32;
+        }
+    }
+
+    /// Add `delta` to every value in both tables. Results too large for a
+    /// `u32` saturate at `u32::MAX`.
+    pub fn add_all(&mut self, delta: u32) {
+        for value in self
+            .crc_table
+            .values_mut()
+            .chain(self.name_table.values_mut())
+        {
+            *value = value.saturating_add(delta);
+        }
+    }
+
     /// Set multiple RSTB entries from an iterator
     pub fn extend<'i, N: Into<TableIndex<'i>>, I: Iterator<Item = (N, u32)>>(&mut self, iter: I) {
         fn inner<'i, I: Iterator<Item = (TableIndex<'i>, u32)>>(
@@ -325,8 +1188,613 @@ impl ResourceSizeTable {
     }
 }
 
+/// Record the name with each hash, or `None` if several names share it
+#[cfg(feature = "alloc")]
+fn add_name_hash(name_hashes: &mut BTreeMap<u32, Option<Name>>, hash: u32, name: Name) {
+    name_hashes
+        .entry(hash)
+        .and_modify(|unique| *unique = None)
+        .or_insert(Some(name));
+}
+
 #[cfg(test)]
 mod test {
     pub(crate) static DATA: &[u8] =
         include_bytes!("../test/ResourceSizeTable.Product.110.rsizetable");
+
+    /// Create an empty scratch directory for a test, unique to this process
+    /// so that concurrent test runs do not remove each other's files
+    #[cfg(feature = "std")]
+    pub(crate) fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("restbl_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn owned_key() {
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        let mut values = std::collections::HashMap::new();
+        for entry in parser.iter() {
+            let value = match entry {
+                crate::bin::TableEntry::Hash(e) => e.value(),
+                crate::bin::TableEntry::Name(e) => e.value(),
+            };
+            values.insert(super::OwnedKey::from(entry), value);
+        }
+        assert_eq!(values.len(), parser.len());
+        let name = "Bake/Scene/MainField_U_30_50.bkres";
+        let key = super::OwnedKey::from(name);
+        assert_eq!(values.get(&key), Some(&64416));
+        assert_eq!(parser.get(&key), Some(64416));
+        assert_eq!(key.to_hash(), crate::util::hash_name(name));
+    }
+
+    #[test]
+    fn hash_algo() {
+        let name = "Bake/Scene/MainField_G_26_43.bkres";
+        let totk = super::HashAlgo::Custom {
+            poly: 0xEDB88320,
+            init: 0xFFFFFFFF,
+            xorout: 0xFFFFFFFF,
+        };
+        assert_eq!(super::HashAlgo::default(), super::HashAlgo::Crc32Totk);
+        assert_eq!(
+            super::hash_name_with(super::HashAlgo::Crc32Totk, name),
+            super::hash(name)
+        );
+        assert_eq!(super::hash_name_with(totk, name), super::hash(name));
+        assert_eq!(
+            super::hash_name_with(totk, name),
+            crate::util::hash_name(name)
+        );
+        let other = super::HashAlgo::Custom {
+            poly: 0x82F63B78,
+            init: 0xFFFFFFFF,
+            xorout: 0xFFFFFFFF,
+        };
+        // CRC-32C check value
+        assert_eq!(super::hash_name_with(other, "123456789"), 0xE3069283);
+    }
+
+    #[test]
+    fn table_index_accessors() {
+        let hash = super::TableIndex::from(7);
+        let name = super::TableIndex::from("Test/Name.bgyml");
+        assert_eq!(hash.as_hash(), Some(7));
+        assert_eq!(hash.as_str(), None);
+        assert_eq!(name.as_hash(), None);
+        assert_eq!(name.as_str(), Some("Test/Name.bgyml"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn table_index_display() {
+        use alloc::string::ToString;
+        assert_eq!(super::TableIndex::from(7).to_string(), "7");
+        let name = super::TableIndex::from(alloc::string::String::from("Test/Name.bgyml"));
+        assert_eq!(name.to_string(), "Test/Name.bgyml");
+        assert_eq!(super::OwnedKey::from(&name).to_string(), name.to_string());
+        let numeric = super::TableIndex::from(alloc::string::String::from("12345"));
+        assert_eq!(numeric.to_string(), "\"12345\"");
+        assert_eq!(
+            super::OwnedKey::from("#Test.bgyml").to_string(),
+            "\"#Test.bgyml\""
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn memory_footprint() {
+        let table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let footprint = table.memory_footprint();
+        let entries = table.crc_table.len() * 8
+            + table.name_table.len() * core::mem::size_of::<(super::Name, u32)>();
+        assert!(footprint > entries);
+        assert!(footprint < entries * 2);
+        assert_eq!(super::ResourceSizeTable::new().memory_footprint(), 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn canonicalize() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(table.canonicalize(), 0);
+        let name = "Test/Overlap.bgyml";
+        table.crc_table.insert(crate::util::hash_name(name), 1);
+        table.name_table.insert(name.into(), 2);
+        let names = table.name_table.clone();
+        assert_eq!(table.canonicalize(), 1);
+        for (name, value) in names {
+            assert_eq!(table.get(name), Some(value));
+            assert!(!table.contains_strict(crate::util::hash_name(&name)));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn ensure_invariants() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(table.ensure_invariants(false).is_ok());
+        let name = "Test/Overlap.bgyml";
+        table.crc_table.insert(crate::util::hash_name(name), 1);
+        table.name_table.insert(name.into(), 2);
+        assert!(matches!(
+            table.ensure_invariants(false),
+            Err(super::Error::Overlap(n)) if n == name
+        ));
+        assert!(table.ensure_invariants(true).is_ok());
+        assert_eq!(table.overlapping_count(), 0);
+        assert_eq!(table.get(name), Some(2));
+        table.name_table.insert("a".repeat(200).as_str().into(), 3);
+        assert!(matches!(
+            table.ensure_invariants(true),
+            Err(super::Error::NameTooLong(160))
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn canonical_paths() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let path = ".\\Bake\\Scene\\MainField_G_26_43.bkres.zs";
+        assert_eq!(table.get(path), None);
+        assert_eq!(table.get_canonical(path), Some(31880));
+        assert_eq!(table.set_canonical(path, 1), Some(31880));
+        assert_eq!(table.get("Bake/Scene/MainField_G_26_43.bkres"), Some(1));
+        let terrain = "TerrainArc/MainField/700003234.hght.ta.zs";
+        assert!(table.name_table.contains_key(&terrain.into()));
+        assert_eq!(table.get_canonical(terrain), table.get(terrain));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn is_canonical() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(table.is_canonical());
+        let name = "Test/Overlap.bgyml";
+        table.crc_table.insert(crate::util::hash_name(name), 1);
+        table.name_table.insert(name.into(), 2);
+        assert_eq!(table.overlapping_count(), 1);
+        assert!(!table.is_canonical());
+        table.canonicalize();
+        // Now demotable, since nothing else shares its hash
+        assert_eq!(table.overlapping_count(), 0);
+        assert!(!table.is_canonical());
+        table.remove(name);
+        table.set(name, 2);
+        assert!(table.is_canonical());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn content_hash() {
+        assert_eq!(
+            super::ResourceSizeTable::new().content_hash(),
+            // FNV-1a of eight zero bytes
+            0xa8c7_f832_281a_39c5
+        );
+        let table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut reordered = super::ResourceSizeTable::new();
+        for (key, value) in table.iter().collect::<Vec<_>>().into_iter().rev() {
+            match key {
+                super::TableIndex::HashIndex(hash) => reordered.crc_table.insert(hash, value),
+                super::TableIndex::StringIndex(name) => {
+                    reordered.name_table.insert(name.as_ref().into(), value)
+                }
+            };
+        }
+        assert_eq!(table.content_hash(), reordered.content_hash());
+        let mut changed = table.clone();
+        changed.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        assert_ne!(table.content_hash(), changed.content_hash());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn hashes_in_range() {
+        let table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        let range = 0x1000_0000..0x2000_0000;
+        assert!(table
+            .hashes_in_range(range.clone())
+            .eq(parser.hashes_in_range(range).map(|e| (e.hash(), e.value()))));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bump_all() {
+        let mut table = super::ResourceSizeTable::new();
+        table.set(1, 100);
+        table.set(2, u32::MAX - 10);
+        table.name_table.insert("Test/Name.bgyml".into(), 1000);
+        table.add_all(20);
+        assert_eq!(table.get(1), Some(120));
+        assert_eq!(table.get(2), Some(u32::MAX));
+        assert_eq!(table.get("Test/Name.bgyml"), Some(1020));
+        table.scale_all(1.5);
+        assert_eq!(table.get(1), Some(180));
+        assert_eq!(table.get(2), Some(u32::MAX));
+        assert_eq!(table.get("Test/Name.bgyml"), Some(1530));
+        table.scale_all(-1.0);
+        assert_eq!(table.get(2), Some(0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_maps() {
+        let table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(
+            super::ResourceSizeTable::from_maps(table.crc_table.clone(), table.name_table.clone())
+                .unwrap(),
+            table
+        );
+        let name = "Test/Overlap.bgyml";
+        let mut crc_table = table.crc_table.clone();
+        crc_table.insert(crate::util::hash_name(name), 1);
+        let mut name_table = table.name_table;
+        name_table.insert(name.into(), 2);
+        assert!(matches!(
+            super::ResourceSizeTable::from_maps(crc_table, name_table),
+            Err(super::Error::Overlap(n)) if n == name
+        ));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn crc_only() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let names = core::mem::take(&mut table.name_table);
+        let data = table.to_binary();
+        let parser = crate::bin::ResTblReader::new(&data).unwrap();
+        assert_eq!(parser.header().name_table_count(), 0);
+        let crc_table = super::ResourceSizeTable::from_parser(&parser).crc_only();
+        assert_eq!(crc_table, table.crc_table);
+        let rebuilt = super::ResourceSizeTable::from_crc_map(crc_table);
+        assert_eq!(rebuilt.to_binary(), data);
+
+        let (name, value) = names.iter().next().map(|(n, v)| (*n, *v)).unwrap();
+        let hash = crate::util::hash_name(&name);
+        let mut table = super::ResourceSizeTable::from_crc_map(rebuilt.crc_table);
+        table.crc_table.insert(hash, value.wrapping_add(1));
+        table.name_table.insert(name, value);
+        let len = table.crc_table.len();
+        let crc_table = table.crc_only();
+        assert_eq!(crc_table.len(), len);
+        assert_eq!(crc_table.get(&hash), Some(&value));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn precedence() {
+        use super::Precedence;
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let name = "Test/Colliding.bgyml";
+        let hash = crate::util::hash_name(name);
+        table.crc_table.insert(hash, 1);
+        table.name_table.insert(name.into(), 2);
+        assert_eq!(table.get(name), Some(2));
+        assert_eq!(
+            table.get_with_precedence(name, Precedence::NameFirst),
+            Some(2)
+        );
+        assert_eq!(
+            table.get_with_precedence(name, Precedence::HashFirst),
+            Some(1)
+        );
+        assert_eq!(
+            table.get_with_precedence(hash, Precedence::NameFirst),
+            Some(1)
+        );
+        let data = table.to_binary();
+        let parser = crate::bin::ResTblReader::new(data.as_slice()).unwrap();
+        for precedence in [Precedence::NameFirst, Precedence::HashFirst] {
+            assert_eq!(
+                parser.get_with_precedence(name, precedence),
+                table.get_with_precedence(name, precedence)
+            );
+        }
+        assert_eq!(parser.get(name), table.get(name));
+        table.crc_table.remove(&hash);
+        assert_eq!(
+            table.get_with_precedence(name, Precedence::HashFirst),
+            Some(2)
+        );
+        table.set(hash, 3);
+        table.name_table.remove(&name.into());
+        assert_eq!(
+            table.get_with_precedence(name, Precedence::NameFirst),
+            Some(3)
+        );
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        assert_eq!(
+            table.get_with_precedence(hashed, Precedence::HashFirst),
+            Some(31880)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn status() {
+        use super::EntryStatus;
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        assert_eq!(table.status(hashed), EntryStatus::Present(31880));
+        table.set(hashed, 0);
+        assert_eq!(table.status(hashed), EntryStatus::Present(0));
+        assert!(table.status(hashed).is_zero());
+        assert_eq!(table.status(hashed).value(), Some(0));
+        table.remove(hashed);
+        assert_eq!(table.status(hashed), EntryStatus::Absent);
+        assert!(!table.status(hashed).is_zero());
+        assert_eq!(table.status(hashed).value(), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn get_located() {
+        use super::EntryKind;
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        assert_eq!(table.get_located(hashed), Some((31880, EntryKind::Hash)));
+        assert_eq!(
+            table.get_located(crate::util::hash_name(hashed)),
+            Some((31880, EntryKind::Hash))
+        );
+        let (name, value) = table
+            .name_table
+            .iter()
+            .next()
+            .map(|(n, v)| (*n, *v))
+            .unwrap();
+        assert_eq!(table.get_located(&*name), Some((value, EntryKind::Name)));
+        table.remove(hashed);
+        assert_eq!(table.get_located(hashed), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_parser_validated() {
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        assert_eq!(
+            super::ResourceSizeTable::from_parser_validated(&parser).unwrap(),
+            super::ResourceSizeTable::from_parser(&parser)
+        );
+        let mut builder = crate::bin::TableBuilder::new();
+        builder.push_hash(1, 10).unwrap();
+        builder.push_hash(2, 20).unwrap();
+        let mut data = builder.finish();
+        // Swap the two hashes
+        let offset = crate::bin::Header::FULL_SIZE;
+        data[offset..][..4].copy_from_slice(&2u32.to_le_bytes());
+        data[offset + 8..][..4].copy_from_slice(&1u32.to_le_bytes());
+        let parser = crate::bin::ResTblReader::new(data.as_slice()).unwrap();
+        assert!(matches!(
+            super::ResourceSizeTable::from_parser_validated(&parser),
+            Err(super::Error::Unsorted)
+        ));
+    }
+
+    #[test]
+    fn const_hash() {
+        const HASH: u32 = crate::hash("Bake/Scene/MainField_G_26_43.bkres");
+        assert_eq!(
+            HASH,
+            crate::util::hash_name("Bake/Scene/MainField_G_26_43.bkres")
+        );
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        let hash = parser
+            .iter()
+            .find_map(|entry| match entry {
+                crate::bin::TableEntry::Hash(entry) if entry.value() == 31880 => Some(entry.hash()),
+                _ => None,
+            })
+            .unwrap();
+        assert!(matches!(hash, HASH));
+        assert_eq!(crate::hash!("Bake/Scene/MainField_G_26_43.bkres"), HASH);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_hash_map() {
+        let mut paths = std::collections::HashMap::new();
+        paths.insert("Pack/Actor/Test.pack".to_string(), 10);
+        paths.insert("Bake/Scene/MainField_G_26_43.bkres".to_string(), 20);
+        let table = super::ResourceSizeTable::try_from(paths.clone()).unwrap();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get("Pack/Actor/Test.pack"), Some(10));
+        assert_eq!(table.get("Bake/Scene/MainField_G_26_43.bkres"), Some(20));
+        paths.insert("a".repeat(160), 30);
+        assert!(matches!(
+            super::ResourceSizeTable::try_from(paths),
+            Err(super::Error::NameTooLong(160))
+        ));
+    }
+
+    #[test]
+    fn name_new() {
+        let name = "Bake/Scene/MainField_G_26_43.bkres";
+        for dirty in [
+            name,
+            "Bake/Scene/MainField_G_26_43.bkres \r\n",
+            "Bake/Scene/MainField_G_26_43.bkres\0\0",
+        ] {
+            let clean = super::Name::new(dirty).unwrap();
+            assert_eq!(clean.as_str(), name);
+            assert_eq!(crate::util::hash_name(&clean), crate::hash(name));
+        }
+        assert!(matches!(
+            super::Name::new("Bake/Scene\0/Test.bkres"),
+            Err(super::Error::NulInName(10))
+        ));
+        assert!(super::Name::new(&format!("{} ", "a".repeat(159))).is_ok());
+    }
+
+    #[test]
+    fn name_truncate_char_boundary() {
+        let long = format!("{}é", "a".repeat(159));
+        let name = super::Name::from(long.as_str());
+        assert_eq!(name.as_str(), &long[..159]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_crc_map() {
+        let crc_table = super::BTreeMap::from([(1, 10), (2, 20)]);
+        let table = super::ResourceSizeTable::from(crc_table.clone());
+        assert_eq!(table.crc_table, crc_table);
+        assert!(table.name_table.is_empty());
+        assert_eq!(table.get(2), Some(20));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn demotion_savings() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let savings = table.demotion_savings();
+        table.name_table.insert("Test/Demotable.bgyml".into(), 1);
+        assert_eq!(table.demotion_savings(), savings + 0xa4 - 8);
+        let name = "Test/Overlap.bgyml";
+        table.crc_table.insert(crate::util::hash_name(name), 1);
+        table.name_table.insert(name.into(), 2);
+        assert_eq!(table.demotion_savings(), savings + 0xa4 - 8);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn rename() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let old = "Bake/Scene/MainField_G_26_43.bkres";
+        assert!(table.rename(old, "Test/Renamed.bkres").unwrap());
+        assert!(!table.contains(old));
+        assert_eq!(table.get("Test/Renamed.bkres"), Some(31880));
+        assert!(!table.rename(old, "Test/Other.bkres").unwrap());
+        assert!(matches!(
+            table.rename("Test/Renamed.bkres", "Bake/Scene/MainField_U_30_50.bkres"),
+            Err(super::Error::KeyExists(_))
+        ));
+        assert!(table.contains("Test/Renamed.bkres"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn merge_all() {
+        use super::ResourceSizeTable;
+        let base = ResourceSizeTable::from_binary(DATA).unwrap();
+        let hashed = "Bake/Scene/MainField_G_26_43.bkres";
+        let moved = "Test/Moved.bgyml";
+        let mut a = base.clone();
+        a.set(hashed, 40000);
+        a.set("Test/A.bgyml", 1);
+        a.name_table.insert(moved.into(), 5);
+        let mut b = ResourceSizeTable::new();
+        b.set(hashed, 50000);
+        b.set("Test/A.bgyml", 3);
+        b.crc_table.insert(crate::util::hash_name(moved), 7);
+        let mut c = ResourceSizeTable::new();
+        c.set(hashed, 100);
+        c.set("Test/C.bgyml", 2);
+        c.name_table.insert(moved.into(), 6);
+        let merged = ResourceSizeTable::merge_all([a, b, c], u32::max);
+        assert_eq!(merged.len(), base.len() + 3);
+        assert_eq!(merged.get(hashed), Some(50000));
+        assert_eq!(merged.get("Test/A.bgyml"), Some(3));
+        assert_eq!(merged.get("Test/C.bgyml"), Some(2));
+        assert_eq!(merged.name_table.get(&moved.into()), Some(&7));
+        assert_eq!(merged.overlapping_count(), 0);
+        let sum = ResourceSizeTable::merge_all([base.clone(), base.clone()], |a, b| a + b);
+        assert_eq!(sum.get(hashed), Some(31880 * 2));
+        assert_eq!(sum.len(), base.len());
+        assert!(ResourceSizeTable::merge_all([], u32::max).is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn to_hash_vec() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let name = "Test/Overlap.bgyml";
+        let hash = crate::util::hash_name(name);
+        table.crc_table.insert(hash, 1);
+        table.name_table.insert(name.into(), 2);
+        let flat = table.to_hash_vec();
+        assert!(flat.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(flat.len() < table.len());
+        assert_eq!(
+            flat.binary_search_by_key(&hash, |(h, _)| *h)
+                .map(|i| flat[i].1),
+            Ok(2)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn partition() {
+        let table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let (small, large) = table.clone().partition(|_, value| value < 4096);
+        assert_eq!(small.len() + large.len(), table.len());
+        assert_eq!(
+            small.name_table.len() + large.name_table.len(),
+            table.name_table.len()
+        );
+        assert!(small.iter().all(|(_, v)| v < 4096));
+        assert!(large.iter().all(|(_, v)| v >= 4096));
+        let mut merged = small;
+        merged.merge(&large);
+        assert_eq!(merged, table);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn merge() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut other = super::ResourceSizeTable::new();
+        other.set("Bake/Scene/MainField_G_26_43.bkres", 1);
+        other.set("Test/New.bgyml", 2);
+        other
+            .name_table
+            .insert("Bake/Scene/MainField_U_30_50.bkres".into(), 3);
+        let len = table.len();
+        table.merge(&other);
+        assert_eq!(table.len(), len + 1);
+        assert_eq!(table.get("Bake/Scene/MainField_G_26_43.bkres"), Some(1));
+        assert_eq!(table.get("Test/New.bgyml"), Some(2));
+        assert_eq!(table.get("Bake/Scene/MainField_U_30_50.bkres"), Some(3));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn merge_name_and_hash() {
+        let name = "Test/Moved.bgyml";
+        let hash = crate::util::hash_name(name);
+        let mut table = super::ResourceSizeTable::new();
+        table.name_table.insert(name.into(), 1);
+        let mut other = super::ResourceSizeTable::new();
+        other.crc_table.insert(hash, 2);
+        table.merge(&other);
+        assert_eq!(table.get(name), Some(2));
+        assert_eq!(table.len(), 1);
+        let mut table = super::ResourceSizeTable::new();
+        table.crc_table.insert(hash, 1);
+        let mut other = super::ResourceSizeTable::new();
+        other.name_table.insert(name.into(), 2);
+        table.merge(&other);
+        assert_eq!(table.name_table.get(&name.into()), Some(&2));
+        assert!(table.crc_table.is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn find_suspicious() {
+        let mut table = super::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(table.find_suspicious(0).count(), 0);
+        table.set("Pack/Actor/Zero.pack", 0);
+        table.set(0xdeadbeef, 16);
+        let found: Vec<_> = table.find_suspicious(32).collect();
+        assert!(found
+            .iter()
+            .any(|(k, v)| matches!(k, super::TableIndex::HashIndex(0xdeadbeef)) && *v == 16));
+        assert!(found.iter().any(|(_, v)| *v == 0));
+        assert!(found.iter().all(|(_, v)| *v < 32));
+    }
 }
@@ -0,0 +1,210 @@
+use alloc::vec::Vec;
+
+use crate::{util::hash_name, Name, OwnedKey, ResourceSizeTable};
+
+/// A single problem found by [`ResourceSizeTable::lint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintFinding {
+    /// A name with no room left for its NUL terminator in the 160-byte name
+    /// field
+    LongName(OwnedKey),
+    /// A name entry whose hash also has an entry in the hash table, so the
+    /// table holds two values for the same resource
+    Overlap(OwnedKey),
+    /// An entry with a value of 0
+    ZeroValue(OwnedKey),
+}
+
+impl LintFinding {
+    /// Get the key of the offending entry
+    pub fn key(&self) -> &OwnedKey {
+        match self {
+            Self::LongName(key) | Self::Overlap(key) | Self::ZeroValue(key) => key,
+        }
+    }
+
+    /// Check if [`ResourceSizeTable::fix`] can resolve this finding
+    pub fn is_fixable(&self) -> bool {
+        matches!(self, Self::Overlap(_))
+    }
+}
+
+/// The health of a table, as found by [`ResourceSizeTable::lint`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LintFinding> {
+        self.findings.iter()
+    }
+}
+
+impl ResourceSizeTable {
+    /// Check the table for problems: over-long names, name entries overlapped
+    /// by hash entries, and zero values. Findings are grouped by kind, in
+    /// table order. Sort order needs no check, since the maps always
+    /// serialize sorted.
+    pub fn lint(&self) -> LintReport {
+        let mut findings = Vec::new();
+        findings.extend(
+            self.name_table
+                .keys()
+                .filter(|name| name.len() >= 160)
+                .map(|name| LintFinding::LongName(OwnedKey::Name(*name))),
+        );
+        findings.extend(
+            self.name_table
+                .keys()
+                .filter(|name| self.crc_table.contains_key(&hash_name(name)))
+                .map(|name| LintFinding::Overlap(OwnedKey::Name(*name))),
+        );
+        findings.extend(
+            self.iter()
+                .filter(|(_, value)| *value == 0)
+                .map(|(key, _)| LintFinding::ZeroValue(OwnedKey::from(&key))),
+        );
+        LintReport { findings }
+    }
+
+    /// Find every name entry whose hash also has an entry in the hash table
+    /// with a different value, returning each name with the value of the
+    /// conflicting hash entry. Unlike a plain overlap check, overlaps where
+    /// both entries agree are not reported, since they cannot change which
+    /// value the game sees.
+    pub fn audit_hashes(&self) -> Vec<(Name, u32)> {
+        self.name_table
+            .iter()
+            .filter_map(|(name, value)| {
+                self.crc_table
+                    .get(&hash_name(name))
+                    .filter(|crc_value| *crc_value != value)
+                    .map(|crc_value| (*name, *crc_value))
+            })
+            .collect()
+    }
+
+    /// Find name entries which cannot be canonical resource paths, and so will
+    /// likely never be looked up by the game. A name is flagged if it:
+    ///
+    /// - contains a `\`, rather than only `/` separators
+    /// - starts with `/` or `./`
+    /// - has no extension, or an empty one, in its final component
+    ///
+    /// Nothing else is checked, so unusual but valid paths are not flagged.
+    /// Names are yielded in table order.
+    pub fn find_malformed_names(&self) -> impl Iterator<Item = &Name> {
+        self.name_table.keys().filter(|name| {
+            name.contains('\\')
+                || name.starts_with('/')
+                || name.starts_with("./")
+                || crate::estimate::extension(name).is_none_or(str::is_empty)
+        })
+    }
+
+    /// Apply the safe fixes for the findings in a report, returning the number
+    /// of findings fixed. Only overlaps are fixed, by removing the hash entry
+    /// so the name entry takes precedence as in
+    /// [`canonicalize`](Self::canonicalize). Other findings need a decision
+    /// from the user and are left alone.
+    pub fn fix(&mut self, report: &LintReport) -> usize {
+        report
+            .iter()
+            .filter(|finding| match finding {
+                LintFinding::Overlap(OwnedKey::Name(name)) => {
+                    self.crc_table.remove(&hash_name(name)).is_some()
+                }
+                _ => false,
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LintFinding;
+    use crate::{test::DATA, OwnedKey, ResourceSizeTable};
+
+    #[test]
+    fn lint() {
+        let mut table = ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(table.lint().is_empty());
+        let name = "Test/Overlap.bgyml";
+        table.crc_table.insert(crate::util::hash_name(name), 1);
+        table.name_table.insert(name.into(), 2);
+        table.set(7, 0);
+        let long = "a".repeat(200);
+        table.name_table.insert(long.as_str().into(), 3);
+        let report = table.lint();
+        assert_eq!(
+            report.findings,
+            [
+                LintFinding::LongName(OwnedKey::from(&long[..160])),
+                LintFinding::Overlap(OwnedKey::from(name)),
+                LintFinding::ZeroValue(OwnedKey::Hash(7)),
+            ]
+        );
+        assert_eq!(table.fix(&report), 1);
+        assert_eq!(table.get(name), Some(2));
+        assert_eq!(table.lint().len(), 2);
+    }
+
+    #[test]
+    fn audit_hashes() {
+        let mut table = ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(table.audit_hashes().is_empty());
+        let agreeing = "Test/Agreeing.bgyml";
+        table.crc_table.insert(crate::util::hash_name(agreeing), 1);
+        table.name_table.insert(agreeing.into(), 1);
+        let conflicting = "Test/Conflicting.bgyml";
+        table
+            .crc_table
+            .insert(crate::util::hash_name(conflicting), 2);
+        table.name_table.insert(conflicting.into(), 3);
+        assert_eq!(table.audit_hashes(), [(conflicting.into(), 2)]);
+    }
+
+    #[test]
+    fn find_malformed_names() {
+        let mut table = ResourceSizeTable::from_binary(DATA).unwrap();
+        assert_eq!(table.find_malformed_names().count(), 0);
+        for name in [
+            "Pack\\Actor\\Test.pack",
+            "/Pack/Actor/Test.pack",
+            "./Pack/Actor/Test.pack",
+            "Pack/Actor/Test",
+            "Pack/Actor.v2/Test",
+            "Pack/Actor/Test.",
+            "Pack/Actor/Test.pack",
+            "Pack/Actor/Test.Product.100.pack",
+        ] {
+            table.name_table.insert(name.into(), 1);
+        }
+        let malformed = table
+            .find_malformed_names()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            malformed,
+            [
+                "./Pack/Actor/Test.pack",
+                "/Pack/Actor/Test.pack",
+                "Pack/Actor.v2/Test",
+                "Pack/Actor/Test",
+                "Pack/Actor/Test.",
+                "Pack\\Actor\\Test.pack",
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::mem::size_of;
+
+use crate::{
+    bin::{self, HashEntry, Header, NameEntry, ResTblReader},
+    util::{hash_name, Name},
+    ResourceSizeTable, Result, TableIndex,
+};
+
+/// An owned table which also remembers the order its entries had in the
+/// source file, so that files which are not sorted can be written back
+/// exactly. The game's own tables are sorted, so this is only needed to
+/// reproduce non-canonical files; [`ResourceSizeTable`] avoids the extra
+/// storage otherwise.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OrderedTable {
+    /// The table contents, which may be edited freely
+    pub table: ResourceSizeTable,
+    crc_order: Vec<u32>,
+    name_order: Vec<Name>,
+}
+
+impl OrderedTable {
+    /// Construct an ordered table from a fast readonly parser, recording the
+    /// order of its entries
+    pub fn from_parser(parser: &ResTblReader<'_>) -> Self {
+        let mut crc_order = Vec::new();
+        let mut name_order = Vec::new();
+        for entry in parser.iter() {
+            match entry {
+                bin::TableEntry::Hash(entry) => crc_order.push(entry.hash()),
+                bin::TableEntry::Name(entry) => name_order.push(entry.name()),
+            }
+        }
+        Self {
+            table: ResourceSizeTable::from_parser(parser),
+            crc_order,
+            name_order,
+        }
+    }
+
+    /// Parse an ordered table from binary form
+    pub fn from_binary(data: impl AsRef<[u8]>) -> Result<Self> {
+        Ok(Self::from_parser(&ResTblReader::new(data.as_ref())?))
+    }
+
+    /// Set the value for a hash or resource name as by
+    /// [`ResourceSizeTable::set`], returning the original value if present. A
+    /// new entry is recorded after every entry already in the order, so
+    /// entries are written in insertion order. A removed entry which is set
+    /// again returns to its original position.
+    pub fn set<'i, I: Into<TableIndex<'i>>>(&mut self, needle: I, value: u32) -> Option<u32> {
+        let needle = needle.into();
+        let hash = match &needle {
+            TableIndex::HashIndex(hash) => *hash,
+            TableIndex::StringIndex(name) => hash_name(name),
+        };
+        let old = self.table.set(needle, value);
+        // `set` only ever adds hash entries
+        if old.is_none() {
+            self.crc_order.push(hash);
+        }
+        old
+    }
+
+    /// Set the value of a name entry, adding it to the name table if absent,
+    /// and returning the original value if present. A new entry is recorded
+    /// in insertion order, as by [`set`](Self::set).
+    pub fn insert_name(&mut self, name: Name, value: u32) -> Option<u32> {
+        let old = self.table.name_table.insert(name, value);
+        if old.is_none() {
+            self.name_order.push(name);
+        }
+        old
+    }
+
+    /// Iterate all entries in file order: hash entries, then name entries,
+    /// each in the order they appeared in the source or were added by
+    /// [`set`](Self::set) or [`insert_name`](Self::insert_name). Entries
+    /// removed since are skipped, and entries added directly to
+    /// [`table`](Self::table) are yielded in sorted order after the others of
+    /// the same table. If a key is recorded more than once, only the first
+    /// occurrence is yielded.
+    pub fn iter_original_order(&self) -> impl Iterator<Item = (TableIndex<'_>, u32)> {
+        let original_hashes = self.crc_order.iter().collect::<BTreeSet<_>>();
+        let mut seen_hashes = BTreeSet::new();
+        let hashes = self
+            .crc_order
+            .iter()
+            .filter(move |hash| seen_hashes.insert(**hash))
+            .chain(
+                self.table
+                    .crc_table
+                    .keys()
+                    .filter(move |hash| !original_hashes.contains(hash)),
+            )
+            .filter_map(|hash| {
+                self.table
+                    .crc_table
+                    .get(hash)
+                    .map(|value| (TableIndex::HashIndex(*hash), *value))
+            });
+        let original_names = self.name_order.iter().collect::<BTreeSet<_>>();
+        let mut seen_names = BTreeSet::new();
+        let names = self
+            .name_order
+            .iter()
+            .filter(move |name| seen_names.insert(**name))
+            .chain(
+                self.table
+                    .name_table
+                    .keys()
+                    .filter(move |name| !original_names.contains(name)),
+            )
+            .filter_map(|name| {
+                self.table
+                    .name_table
+                    .get(name)
+                    .map(|value| (TableIndex::from(name.as_str()), *value))
+            });
+        hashes.chain(names)
+    }
+
+    /// Write the table in its binary format to bytes, with entries in the
+    /// order given by [`iter_original_order`](Self::iter_original_order).
+    ///
+    /// This is for reproducing the exact output of other tools in interop
+    /// tests. Unless the entries happen to be sorted, the output breaks the
+    /// binary searches done by [`ResTblReader`] and by the game, so lookups
+    /// may miss entries which are present. Use
+    /// [`ResourceSizeTable::to_binary`] for files meant to be used.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut buffer = alloc::vec![0u8; self.table.binary_size()];
+        Header::with_counts(
+            self.table.crc_table.len() as u32,
+            self.table.name_table.len() as u32,
+        )
+        .write(&mut buffer);
+        let mut pos = Header::FULL_SIZE;
+        for (key, value) in self.iter_original_order() {
+            match key {
+                TableIndex::HashIndex(hash) => {
+                    HashEntry::new(hash, value).write(&mut buffer[pos..]);
+                    pos += size_of::<HashEntry>();
+                }
+                TableIndex::StringIndex(name) => {
+                    NameEntry::new(Name::from(name.as_ref()), value).write(&mut buffer[pos..]);
+                    pos += size_of::<NameEntry>();
+                }
+            }
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OrderedTable;
+    use crate::{bin::Header, test::DATA, ResourceSizeTable, TableIndex};
+
+    #[test]
+    fn original_order() {
+        let table = OrderedTable::from_binary(DATA).unwrap();
+        assert_eq!(table.to_binary(), DATA);
+        let mut builder = crate::bin::TableBuilder::new();
+        builder.push_hash(1, 10).unwrap();
+        builder.push_hash(2, 20).unwrap();
+        builder.push_name("A.bgyml", 30).unwrap();
+        let mut data = builder.finish();
+        // Swap the two hashes
+        data[Header::FULL_SIZE..][..4].copy_from_slice(&2u32.to_le_bytes());
+        data[Header::FULL_SIZE + 8..][..4].copy_from_slice(&1u32.to_le_bytes());
+        assert_ne!(
+            ResourceSizeTable::from_binary(&data).unwrap().to_binary(),
+            data
+        );
+        let mut table = OrderedTable::from_binary(&data).unwrap();
+        assert_eq!(table.to_binary(), data);
+        table.table.set(0, 5);
+        assert!(table.iter_original_order().eq([
+            (TableIndex::HashIndex(2), 10),
+            (TableIndex::HashIndex(1), 20),
+            (TableIndex::HashIndex(0), 5),
+            (TableIndex::from("A.bgyml"), 30),
+        ]));
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut table = OrderedTable::default();
+        assert_eq!(table.set(5, 50), None);
+        assert_eq!(table.set("Test/Hashed.bgyml", 30), None);
+        assert_eq!(table.set(5, 55), Some(50));
+        assert_eq!(table.insert_name("Test/B.bgyml".into(), 2), None);
+        assert_eq!(table.insert_name("Test/A.bgyml".into(), 1), None);
+        let hashed = crate::util::hash_name("Test/Hashed.bgyml");
+        assert!(table.iter_original_order().eq([
+            (TableIndex::HashIndex(5), 55),
+            (TableIndex::HashIndex(hashed), 30),
+            (TableIndex::from("Test/B.bgyml"), 2),
+            (TableIndex::from("Test/A.bgyml"), 1),
+        ]));
+        let data = table.to_binary();
+        assert_eq!(OrderedTable::from_binary(&data).unwrap().to_binary(), data);
+        assert_eq!(ResourceSizeTable::from_binary(&data).unwrap(), table.table);
+        assert_ne!(table.table.to_binary(), data);
+        let parser = crate::bin::ResTblReader::new(data.as_slice()).unwrap();
+        assert!(matches!(
+            parser.verify_sorted(),
+            Err(crate::Error::Unsorted)
+        ));
+        table.table.remove(5);
+        table.set(5, 5);
+        assert_eq!(
+            table.iter_original_order().next(),
+            Some((TableIndex::HashIndex(5), 5))
+        );
+    }
+}
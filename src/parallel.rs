@@ -0,0 +1,77 @@
+use rayon::prelude::*;
+
+use crate::{ResourceSizeTable, TableIndex};
+
+impl ResourceSizeTable {
+    /// Call `f` with every entry in the table, spreading the calls across
+    /// rayon's thread pool. The order of the calls is unspecified.
+    pub fn par_for_each(&self, f: impl Fn(TableIndex<'_>, u32) + Sync) {
+        rayon::join(
+            || {
+                self.crc_table
+                    .par_iter()
+                    .for_each(|(hash, value)| f(TableIndex::HashIndex(*hash), *value))
+            },
+            || {
+                self.name_table
+                    .par_iter()
+                    .for_each(|(name, value)| f(TableIndex::from(name), *value))
+            },
+        );
+    }
+
+    /// Replace the value of every entry with the result of `f`, which is
+    /// given the entry's key and current value. The calls are spread across
+    /// rayon's thread pool, in unspecified order.
+    pub fn par_map_values(&mut self, f: impl Fn(TableIndex<'_>, u32) -> u32 + Sync) {
+        let f = &f;
+        rayon::join(
+            || {
+                self.crc_table
+                    .par_iter_mut()
+                    .for_each(|(hash, value)| *value = f(TableIndex::HashIndex(*hash), *value))
+            },
+            || {
+                self.name_table
+                    .par_iter_mut()
+                    .for_each(|(name, value)| *value = f(TableIndex::from(name), *value))
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    use crate::{test::DATA, ResourceSizeTable};
+
+    #[test]
+    fn par_for_each() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let count = AtomicUsize::new(0);
+        let total = AtomicU64::new(0);
+        table.par_for_each(|key, value| {
+            assert_eq!(table.get(key), Some(value));
+            count.fetch_add(1, Ordering::Relaxed);
+            total.fetch_add(value as u64, Ordering::Relaxed);
+        });
+        assert_eq!(count.into_inner(), table.len());
+        assert_eq!(total.into_inner(), table.summary().total);
+    }
+
+    #[test]
+    fn par_map_values() {
+        let mut table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut expected = table.clone();
+        expected.add_all(1);
+        table.par_map_values(|_, value| value.saturating_add(1));
+        assert_eq!(table, expected);
+        table.par_map_values(|key, _| match key {
+            crate::TableIndex::HashIndex(hash) => hash,
+            crate::TableIndex::StringIndex(_) => 0,
+        });
+        assert!(table.crc_table.iter().all(|(hash, value)| hash == value));
+        assert!(table.name_table.values().all(|value| *value == 0));
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers for the file naming conventions of RSTB files themselves, and for
+//! the form of the resource paths they contain.
+//!
+//! TOTK ships its table as
+//! `System/Resource/ResourceSizeTable.Product.<version>.rsizetable.zs`, while
+//! some tools keep the `.srsizetable` name from BOTW's conventions. Either may
+//! be zstd compressed, adding a `.zs` suffix.
+
+/// Extensions of uncompressed RSTB files, without the leading dot
+const RSTB_EXTENSIONS: &[&str] = &["rsizetable", "srsizetable"];
+
+/// Remove a trailing `.zs` compression suffix from a path, if present, giving
+/// the logical name of the file
+pub fn strip_zs(path: &str) -> &str {
+    path.strip_suffix(".zs").unwrap_or(path)
+}
+
+/// Check if a path looks like an RSTB file: ending in `.rsizetable` or
+/// `.srsizetable`, optionally followed by `.zs`. Only the name is checked, not
+/// the contents.
+pub fn is_rstb_path(path: &str) -> bool {
+    let path = strip_zs(path);
+    RSTB_EXTENSIONS.iter().any(|ext| {
+        path.strip_suffix(ext)
+            .is_some_and(|rest| rest.ends_with('.') && rest.len() > 1)
+    })
+}
+
+/// Convert a resource path to the form used for RSTB keys, so that it hashes
+/// the same as the game's own entry for the resource. Exactly these changes
+/// are made, in order:
+///
+/// 1. Every `\` is replaced with `/`
+/// 2. Any leading `./` and `/` are removed, repeatedly
+/// 3. A trailing `.zs` is removed, as by [`strip_zs`], since the table
+///    stores the sizes of decompressed files under their decompressed names.
+///    The exception is TerrainArc archives (`.ta.zs`), which the table lists
+///    under their compressed names.
+///
+/// Nothing else is normalized: in particular, case is preserved and `..` is
+/// not resolved. The path is only copied if it contains a `\`.
+#[cfg(feature = "alloc")]
+pub fn canonicalize_path(path: &str) -> alloc::borrow::Cow<'_, str> {
+    fn trim(mut path: &str) -> &str {
+        while let Some(rest) = path.strip_prefix("./").or_else(|| path.strip_prefix('/')) {
+            path = rest;
+        }
+        if path.ends_with(".ta.zs") {
+            path
+        } else {
+            strip_zs(path)
+        }
+    }
+    if path.contains('\\') {
+        alloc::string::String::from(trim(&path.replace('\\', "/"))).into()
+    } else {
+        trim(path).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rstb_path() {
+        assert!(is_rstb_path(
+            "System/Resource/ResourceSizeTable.Product.110.rsizetable.zs"
+        ));
+        assert!(is_rstb_path("ResourceSizeTable.Product.srsizetable"));
+        assert!(is_rstb_path("Test.rsizetable"));
+        assert!(!is_rstb_path(".rsizetable"));
+        assert!(!is_rstb_path("Test.rsizetable.yml"));
+        assert!(!is_rstb_path("Test.bgyml.zs"));
+        assert!(!is_rstb_path("Testrsizetable"));
+        assert_eq!(
+            strip_zs("ResourceSizeTable.Product.110.rsizetable.zs"),
+            "ResourceSizeTable.Product.110.rsizetable"
+        );
+        assert_eq!(strip_zs("Test.rsizetable"), "Test.rsizetable");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn canonical() {
+        let expected = "Bake/Scene/MainField_G_26_43.bkres";
+        for path in [
+            "Bake/Scene/MainField_G_26_43.bkres",
+            "./Bake/Scene/MainField_G_26_43.bkres",
+            "/Bake/Scene/MainField_G_26_43.bkres.zs",
+            ".\\Bake\\Scene\\MainField_G_26_43.bkres.zs",
+        ] {
+            assert_eq!(canonicalize_path(path), expected);
+        }
+        assert!(matches!(
+            canonicalize_path("./Test.bgyml"),
+            alloc::borrow::Cow::Borrowed("Test.bgyml")
+        ));
+        assert_eq!(canonicalize_path("Dir/../Test.BGYML"), "Dir/../Test.BGYML");
+        assert_eq!(
+            canonicalize_path("./TerrainArc/MainField/700003234.hght.ta.zs"),
+            "TerrainArc/MainField/700003234.hght.ta.zs"
+        );
+    }
+}
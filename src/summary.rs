@@ -0,0 +1,166 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{ResourceSizeTable, TableIndex};
+
+/// At-a-glance statistics about a table, as computed by
+/// [`ResourceSizeTable::summary`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TableSummary {
+    /// The total number of entries
+    pub entries: usize,
+    /// The number of hash entries
+    pub hash_entries: usize,
+    /// The number of name entries
+    pub name_entries: usize,
+    /// The smallest value, or `None` if the table is empty
+    pub min: Option<u32>,
+    /// The largest value, or `None` if the table is empty
+    pub max: Option<u32>,
+    /// The sum of all values
+    pub total: u64,
+    /// The number of entries with a value of 0
+    pub zero_values: usize,
+    /// Statistics over the hash entries only
+    pub hash_stats: ValueStats,
+    /// Statistics over the name entries only
+    pub name_stats: ValueStats,
+}
+
+impl TableSummary {
+    /// Get the mean value, or `None` if the table is empty
+    pub fn mean(&self) -> Option<f64> {
+        (self.entries > 0).then(|| self.total as f64 / self.entries as f64)
+    }
+}
+
+/// Statistics over the values of one kind of entry, as found in a
+/// [`TableSummary`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ValueStats {
+    /// The number of entries
+    pub count: usize,
+    /// The smallest value, or `None` if there are no entries
+    pub min: Option<u32>,
+    /// The largest value, or `None` if there are no entries
+    pub max: Option<u32>,
+    /// The sum of all values
+    pub total: u64,
+    /// The number of entries with a value of 0
+    pub zero_values: usize,
+}
+
+impl ValueStats {
+    fn from_values<'a>(values: impl Iterator<Item = &'a u32>) -> Self {
+        let mut stats = Self::default();
+        for value in values {
+            let value = *value;
+            stats.count += 1;
+            stats.min = Some(stats.min.map_or(value, |min| min.min(value)));
+            stats.max = Some(stats.max.map_or(value, |max| max.max(value)));
+            stats.total += value as u64;
+            stats.zero_values += usize::from(value == 0);
+        }
+        stats
+    }
+
+    /// Get the mean value, or `None` if there are no entries
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.total as f64 / self.count as f64)
+    }
+}
+
+impl ResourceSizeTable {
+    /// Compute summary statistics over every entry in the table, and over
+    /// the hash and name entries separately, in a single pass
+    pub fn summary(&self) -> TableSummary {
+        let hash_stats = ValueStats::from_values(self.crc_table.values());
+        let name_stats = ValueStats::from_values(self.name_table.values());
+        TableSummary {
+            entries: hash_stats.count + name_stats.count,
+            hash_entries: hash_stats.count,
+            name_entries: name_stats.count,
+            min: hash_stats.min.into_iter().chain(name_stats.min).min(),
+            max: hash_stats.max.into_iter().chain(name_stats.max).max(),
+            total: hash_stats.total + name_stats.total,
+            zero_values: hash_stats.zero_values + name_stats.zero_values,
+            hash_stats,
+            name_stats,
+        }
+    }
+
+    /// Index the table by value, listing the keys of every entry with each
+    /// value, such as to spot many entries sharing a placeholder value. Keys
+    /// are listed in table order. This allocates in proportion to the number
+    /// of entries, which is significant for a full table.
+    pub fn group_by_value(&self) -> BTreeMap<u32, Vec<TableIndex<'_>>> {
+        let mut groups = BTreeMap::<u32, Vec<_>>::new();
+        for (key, value) in self.iter() {
+            groups.entry(value).or_default().push(key);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::DATA, ResourceSizeTable};
+
+    #[test]
+    fn summary() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let summary = table.summary();
+        assert_eq!(summary.entries, table.len());
+        assert_eq!(summary.hash_entries, 379538);
+        assert_eq!(summary.name_entries, 32);
+        let values = table.iter().map(|(_, value)| value).collect::<Vec<_>>();
+        assert_eq!(summary.min, values.iter().copied().min());
+        assert_eq!(summary.max, values.iter().copied().max());
+        assert_eq!(summary.total, values.iter().map(|v| *v as u64).sum::<u64>());
+        assert_eq!(
+            summary.zero_values,
+            values.iter().filter(|v| **v == 0).count()
+        );
+        assert!(summary.mean().unwrap() > 0.0);
+        assert_eq!(ResourceSizeTable::new().summary().mean(), None);
+    }
+
+    #[test]
+    fn kind_stats() {
+        let table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let summary = table.summary();
+        let (hash, name) = (summary.hash_stats, summary.name_stats);
+        assert_eq!(hash.count, summary.hash_entries);
+        assert_eq!(name.count, summary.name_entries);
+        assert_eq!(hash.total + name.total, summary.total);
+        assert_eq!(hash.zero_values + name.zero_values, summary.zero_values);
+        assert_eq!(hash.max.max(name.max), summary.max);
+        assert_eq!(name.max, table.name_table.values().copied().max());
+        assert_eq!(hash.min, table.crc_table.values().copied().min());
+        assert_eq!(
+            name.mean(),
+            Some(name.total as f64 / summary.name_entries as f64)
+        );
+        let mut table = ResourceSizeTable::new();
+        table.set(1, 5);
+        let summary = table.summary();
+        assert_eq!(summary.name_stats, super::ValueStats::default());
+        assert_eq!(summary.name_stats.mean(), None);
+        assert_eq!(summary.min, Some(5));
+    }
+
+    #[test]
+    fn group_by_value() {
+        let mut table = ResourceSizeTable::from_binary(DATA).unwrap();
+        let groups = table.group_by_value();
+        assert_eq!(groups.values().map(Vec::len).sum::<usize>(), table.len());
+        for (value, keys) in &groups {
+            assert!(keys
+                .iter()
+                .all(|key| table.get(key.clone()) == Some(*value)));
+        }
+        let placeholder = table.summary().max.unwrap() + 1;
+        table.set(1, placeholder);
+        table.set("Test/Placeholder.bgyml", placeholder);
+        assert_eq!(table.group_by_value()[&placeholder].len(), 2);
+    }
+}
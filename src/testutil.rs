@@ -0,0 +1,69 @@
+//! Assertion helpers for downstream tests (`testutil` feature)
+use crate::ResourceSizeTable;
+
+/// Parse a binary RSTB into an owned table, serialize it back, and check that
+/// both the reparsed table and the bytes match the original.
+///
+/// # Panics
+///
+/// Panics if the data does not parse, or with a description of the first
+/// mismatch if the round trip is not exact.
+pub fn assert_roundtrip(bytes: &[u8]) {
+    let table = ResourceSizeTable::from_binary(bytes).expect("Original RSTB should parse");
+    let written = table.to_binary();
+    let reparsed = ResourceSizeTable::from_binary(&written).expect("Serialized RSTB should parse");
+    assert_tables_eq(&table, &reparsed);
+    if let Some(pos) = bytes.iter().zip(written.iter()).position(|(a, b)| a != b) {
+        panic!(
+            "Serialized RSTB differs from the original at offset {pos:#x}: {:#04x} != {:#04x}",
+            written[pos], bytes[pos]
+        );
+    }
+    assert_eq!(
+        written.len(),
+        bytes.len(),
+        "Serialized RSTB length differs from the original"
+    );
+}
+
+/// Parse a binary RSTB into an owned table, convert it to YAML and back, and
+/// check that every resource keeps its value. Entries may move between the
+/// name and hash tables, since the text format does not record which table
+/// held them.
+///
+/// # Panics
+///
+/// Panics if the data or the YAML does not parse, or listing the differing
+/// entries if any value is lost.
+#[cfg(feature = "yaml")]
+pub fn assert_text_roundtrip(bytes: &[u8]) {
+    let table = ResourceSizeTable::from_binary(bytes).expect("Original RSTB should parse");
+    let reparsed =
+        ResourceSizeTable::from_text(table.to_text()).expect("Serialized YAML should parse");
+    let diff = table.diff(&reparsed);
+    assert!(
+        diff.is_empty(),
+        "YAML round trip changed {} entries: {:?}",
+        diff.len(),
+        diff
+    );
+}
+
+fn assert_tables_eq(table: &ResourceSizeTable, other: &ResourceSizeTable) {
+    assert_eq!(
+        table.crc_table.len(),
+        other.crc_table.len(),
+        "Hash table entry counts differ"
+    );
+    for (entry1, entry2) in table.crc_table.iter().zip(other.crc_table.iter()) {
+        assert_eq!(entry1, entry2, "Hash table entries differ");
+    }
+    assert_eq!(
+        table.name_table.len(),
+        other.name_table.len(),
+        "Name table entry counts differ"
+    );
+    for (entry1, entry2) in table.name_table.iter().zip(other.name_table.iter()) {
+        assert_eq!(entry1, entry2, "Name table entries differ");
+    }
+}
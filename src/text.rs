@@ -15,8 +15,17 @@ impl bin::ResTblReader<'_> {
                 }
                 bin::TableEntry::Name(entry) => {
                     let name = entry.name();
+                    let quoted = TextKey::needs_quotes(&name);
+                    if quoted {
+                        buffer[pos] = b'"';
+                        pos += 1;
+                    }
                     buffer[pos..pos + name.len()].copy_from_slice(name.as_bytes());
                     pos += name.len();
+                    if quoted {
+                        buffer[pos] = b'"';
+                        pos += 1;
+                    }
                     buffer[pos..pos + 2].copy_from_slice(b": ".as_slice());
                     pos += 2;
                     pos += lexical_core::write(entry.value(), &mut buffer[pos..]).len();
@@ -31,8 +40,9 @@ impl bin::ResTblReader<'_> {
     pub fn write_text_to_buf(&self, buffer: &mut [u8]) -> Result<usize> {
         let min_crc_size = self.header().crc_table_count() as usize
             * (<u32 as lexical_core::FormattedSize>::FORMATTED_SIZE * 2 + 3);
+        // Names may be quoted, which takes 2 more bytes
         let min_name_size = self.header().name_table_count() as usize
-            * (160 + <u32 as lexical_core::FormattedSize>::FORMATTED_SIZE + 3);
+            * (160 + <u32 as lexical_core::FormattedSize>::FORMATTED_SIZE + 5);
         let min_size = min_crc_size + min_name_size;
         if buffer.len() < min_size {
             Err(Error::InsufficientBuffer(buffer.len(), min_size))
@@ -49,7 +59,21 @@ impl bin::ResTblReader<'_> {
                     writeln!(writer, "{}: {}", entry.hash(), entry.value())?;
                 }
                 bin::TableEntry::Name(entry) => {
-                    writeln!(writer, "{}: {}", entry.name(), entry.value())?;
+                    writeln!(writer, "{}: {}", TextKey(&entry.name()), entry.value())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the table as YAML text into any [`core::fmt::Write`], such as an
+    /// existing `String` or a [`core::fmt::Formatter`], without allocating
+    pub fn fmt_text(&self, f: &mut impl core::fmt::Write) -> core::fmt::Result {
+        for entry in self.iter() {
+            match entry {
+                bin::TableEntry::Hash(entry) => writeln!(f, "{}: {}", entry.hash(), entry.value())?,
+                bin::TableEntry::Name(entry) => {
+                    writeln!(f, "{}: {}", TextKey(&entry.name()), entry.value())?
                 }
             }
         }
@@ -63,7 +87,7 @@ impl bin::ResTblReader<'_> {
             let min_crc_size = self.header().crc_table_count() as usize
                 * (<u32 as lexical_core::FormattedSize>::FORMATTED_SIZE * 2 + 3);
             let min_name_size = self.header().name_table_count() as usize
-                * (160 + <u32 as lexical_core::FormattedSize>::FORMATTED_SIZE + 3);
+                * (160 + <u32 as lexical_core::FormattedSize>::FORMATTED_SIZE + 5);
             let min_size = min_crc_size + min_name_size;
             let mut string = Vec::with_capacity(min_size);
             self.write_text(&mut string)
@@ -72,16 +96,10 @@ impl bin::ResTblReader<'_> {
         }
         #[cfg(not(feature = "std"))]
         {
-            self.iter()
-                .map(|entry| match entry {
-                    bin::TableEntry::Hash(entry) => {
-                        alloc::format!("{}: {}\n", entry.hash(), entry.value())
-                    }
-                    bin::TableEntry::Name(entry) => {
-                        alloc::format!("{}: {}\n", entry.name(), entry.value())
-                    }
-                })
-                .collect()
+            let mut string = alloc::string::String::new();
+            self.fmt_text(&mut string)
+                .expect("Writing to a String should never fail");
+            string
         }
     }
 }
@@ -89,48 +107,194 @@ impl bin::ResTblReader<'_> {
 #[cfg(feature = "alloc")]
 impl super::ResourceSizeTable {
     pub fn to_text(&self) -> alloc::string::String {
+        let mut string = alloc::string::String::new();
+        self.fmt_text(&mut string)
+            .expect("Writing to a String should never fail");
+        string
+    }
+
+    /// Write the table as YAML text into any [`core::fmt::Write`], such as an
+    /// existing `String` or a [`core::fmt::Formatter`], without allocating an
+    /// intermediate `String` like [`to_text`](Self::to_text)
+    pub fn fmt_text(&self, f: &mut impl core::fmt::Write) -> core::fmt::Result {
+        for (hash, value) in &self.crc_table {
+            writeln!(f, "{hash}: {value}")?;
+        }
+        for (name, value) in &self.name_table {
+            writeln!(f, "{}: {value}", TextKey(name))?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the table to YAML text like [`to_text`](Self::to_text), but
+    /// with Windows (`\r\n`) line endings
+    pub fn to_text_crlf(&self) -> alloc::string::String {
         self.crc_table
             .iter()
-            .map(|(k, v)| alloc::format!("{k}: {v}\n"))
+            .map(|(k, v)| alloc::format!("{k}: {v}\r\n"))
             .chain(
                 self.name_table
                     .iter()
-                    .map(|(k, v)| alloc::format!("{k}: {v}\n")),
+                    .map(|(k, v)| alloc::format!("{}: {v}\r\n", TextKey(k))),
             )
             .collect()
     }
 
+    /// Parse a new owned table from YAML text of `key: value` lines, where
+    /// each key is a hash or resource name. A key wrapped in double quotes is
+    /// always a name, which is how names that look like hashes (all digits)
+    /// are written so they round-trip. Blank lines and comment lines
+    /// starting with `#` are skipped, and the space after the colon is
+    /// optional; any other line without a colon or without a valid number
+    /// after it is an error. The table starts empty, so it holds only the
+    /// entries in the text; use
+    /// [`apply_text`](Self::apply_text) to overlay text onto an existing
+    /// table instead.
     pub fn from_text(text: impl AsRef<str>) -> Result<Self> {
         fn inner(text: &str) -> Result<ResourceSizeTable> {
             let mut table = ResourceSizeTable::default();
-            for line in text.lines() {
-                let mut split = line.split(": ");
-                let key = split.next().ok_or_else(|| Error::YamlError(line.into()))?;
-                let value = split
-                    .next()
-                    .ok_or_else(|| Error::YamlError(line.into()))?
-                    .parse::<u32>()?;
-                match key.parse::<u32>() {
-                    Ok(hash) => {
-                        table.crc_table.insert(hash, value);
-                    }
-                    Err(_) => {
-                        let hash = util::hash_name(key);
-                        match table.crc_table.entry(hash) {
-                            alloc::collections::btree_map::Entry::Occupied(_) => {
-                                table.name_table.insert(key.into(), value);
-                            }
-                            alloc::collections::btree_map::Entry::Vacant(entry) => {
-                                entry.insert(value);
-                            }
-                        }
-                    }
+            for (i, line) in text.lines().enumerate() {
+                if let Some((key, value)) = parse_text_line(i + 1, line)? {
+                    table.insert_text_entry(key, value);
                 }
             }
             Ok(table)
         }
         inner(text.as_ref())
     }
+
+    /// Overlay YAML text onto this table, setting each entry in the text and
+    /// leaving every other entry untouched. Unlike
+    /// [`from_text`](Self::from_text), nothing is discarded. Resource names
+    /// are resolved as by [`set`](Self::set). The whole text is parsed before
+    /// any entry is set, so on error the table is unchanged.
+    pub fn apply_text(&mut self, text: impl AsRef<str>) -> Result<()> {
+        fn inner(tbl: &mut ResourceSizeTable, text: &str) -> Result<()> {
+            let entries = text
+                .lines()
+                .enumerate()
+                .filter_map(|(i, line)| parse_text_line(i + 1, line).transpose())
+                .collect::<Result<alloc::vec::Vec<_>>>()?;
+            for (key, value) in entries {
+                match parse_text_key(key) {
+                    Ok(hash) => tbl.set(hash, value),
+                    Err(name) => tbl.set(name, value),
+                };
+            }
+            Ok(())
+        }
+        inner(self, text.as_ref())
+    }
+
+    /// Compute the changes [`apply_text`](Self::apply_text) would make for a
+    /// YAML patch, without modifying this table. Parse errors are returned
+    /// with their line numbers just as `apply_text` would return them. This
+    /// works on a copy of the table, so it costs as much as a
+    /// [`diff`](Self::diff) of the whole table.
+    pub fn preview_text_patch(&self, patch: &str) -> Result<crate::RstbDiff> {
+        let mut patched = self.clone();
+        patched.apply_text(patch)?;
+        Ok(self.diff(&patched))
+    }
+
+    /// Parse an owned table from YAML text, reading it line by line so the
+    /// whole document never needs to be held in memory at once. Errors report
+    /// the (1-based) line number on which they occurred.
+    #[cfg(feature = "std")]
+    pub fn from_text_reader(mut reader: impl std::io::BufRead) -> Result<Self> {
+        let mut table = ResourceSizeTable::default();
+        let mut line = alloc::string::String::new();
+        let mut line_no = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            line_no += 1;
+            let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+            let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+            if let Some((key, value)) = parse_text_line(line_no, trimmed)? {
+                table.insert_text_entry(key, value);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Serialize the table to YAML, parse it back, and return the differences
+    /// from this table, so that any entry the text format fails to preserve
+    /// shows up. This should always be empty. If the text cannot be parsed
+    /// back at all, every entry is reported as removed.
+    pub fn text_roundtrip_diff(&self) -> crate::RstbDiff {
+        let reparsed = Self::from_text(self.to_text()).unwrap_or_default();
+        self.diff(&reparsed)
+    }
+
+    /// Compare this table against a baseline YAML document, returning only
+    /// the differing entries as YAML lines. Lines prefixed with `-` give the
+    /// baseline value and lines prefixed with `+` give the current value, so
+    /// a changed entry produces one of each.
+    pub fn diff_text(&self, baseline: &str) -> Result<alloc::string::String> {
+        let baseline = Self::from_text(baseline)?;
+        let mut text = alloc::string::String::new();
+        for (key, old, new) in baseline.diff(self).iter() {
+            if let Some(old) = old {
+                text.push_str(&alloc::format!("-{key}: {old}\n"));
+            }
+            if let Some(new) = new {
+                text.push_str(&alloc::format!("+{key}: {new}\n"));
+            }
+        }
+        Ok(text)
+    }
+
+    fn insert_text_entry(&mut self, key: &str, value: u32) {
+        match parse_text_key(key) {
+            Ok(hash) => {
+                self.crc_table.insert(hash, value);
+            }
+            Err(name) => {
+                let hash = util::hash_name(name);
+                match self.crc_table.entry(hash) {
+                    alloc::collections::btree_map::Entry::Occupied(_) => {
+                        self.name_table.insert(name.into(), value);
+                    }
+                    alloc::collections::btree_map::Entry::Vacant(entry) => {
+                        entry.insert(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Classify a YAML key as a hash (`Ok`) or a resource name (`Err`). A key
+/// wrapped in double quotes is always a name, with the quotes removed.
+#[cfg(feature = "alloc")]
+fn parse_text_key(key: &str) -> core::result::Result<u32, &str> {
+    match key.strip_prefix('"').and_then(|key| key.strip_suffix('"')) {
+        Some(name) => Err(name),
+        None => key.parse::<u32>().map_err(|_| key),
+    }
+}
+
+/// Split a single `key: value` YAML line, tagging any error with its line
+/// number. The space after the colon is optional, and surrounding whitespace
+/// around the value is ignored. Returns `None` for blank lines and comment
+/// lines (starting with `#`), which are skipped rather than treated as errors.
+#[cfg(feature = "alloc")]
+fn parse_text_line(line_no: usize, line: &str) -> Result<Option<(&str, u32)>> {
+    let trimmed = line.trim_start();
+    if trimmed.trim_end().is_empty() || trimmed.starts_with('#') {
+        return Ok(None);
+    }
+    let (key, value) = line
+        .rsplit_once(':')
+        .ok_or_else(|| Error::YamlError(line_no, line.into()))?;
+    let value = value
+        .trim()
+        .parse::<u32>()
+        .map_err(|e| Error::YamlInvalidNumber(line_no, e))?;
+    Ok(Some((key, value)))
 }
 
 #[cfg(test)]
@@ -164,4 +328,172 @@ mod test {
         let text = parser.to_text();
         println!("{text}");
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn diff_text() {
+        let mut table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let baseline = table.to_text();
+        assert_eq!(table.diff_text(&baseline).unwrap(), "");
+        let hash = crate::util::hash_name("Bake/Scene/MainField_G_26_43.bkres");
+        table.set(hash, 1);
+        table.set(7, 2);
+        assert_eq!(
+            table.diff_text(&baseline).unwrap(),
+            alloc::format!("+7: 2\n-{hash}: 31880\n+{hash}: 1\n")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn apply_text() {
+        let base = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut table = base.clone();
+        table
+            .apply_text("Bake/Scene/MainField_G_26_43.bkres: 1\nTest/New.bgyml: 2")
+            .unwrap();
+        assert_eq!(table.len(), base.len() + 1);
+        assert_eq!(table.get("Bake/Scene/MainField_G_26_43.bkres"), Some(1));
+        assert_eq!(table.get("Test/New.bgyml"), Some(2));
+        assert_eq!(base.diff(&table).len(), 2);
+        assert!(table.apply_text("Test/New.bgyml: 3\nbad").is_err());
+        assert_eq!(table.get("Test/New.bgyml"), Some(2));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fmt_text() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let mut report = String::from("Table:\n");
+        table.fmt_text(&mut report).unwrap();
+        assert_eq!(
+            report.strip_prefix("Table:\n"),
+            Some(table.to_text().as_str())
+        );
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        let mut text = String::new();
+        parser.fmt_text(&mut text).unwrap();
+        assert_eq!(text, table.to_text());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn numeric_names() {
+        let mut table = crate::ResourceSizeTable::new();
+        table.name_table.insert("12345".into(), 1);
+        table.name_table.insert("#Comment.bgyml".into(), 2);
+        let text = table.to_text();
+        assert_eq!(text, "\"#Comment.bgyml\": 2\n\"12345\": 1\n");
+        let parsed = crate::ResourceSizeTable::from_text(&text).unwrap();
+        assert_eq!(parsed.get("12345"), Some(1));
+        assert_eq!(parsed.get("#Comment.bgyml"), Some(2));
+        assert_eq!(parsed.get(12345), None);
+        assert!(table.text_roundtrip_diff().is_empty());
+        let data = table.to_binary();
+        let parser = crate::bin::ResTblReader::new(data.as_slice()).unwrap();
+        assert_eq!(parser.to_text(), text);
+        let mut buffer = vec![0u8; 1024];
+        let len = parser.write_text_to_buf(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], text.as_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn long_quoted_name() {
+        let mut table = crate::ResourceSizeTable::new();
+        let name = format!("#{}", "a".repeat(158));
+        table.name_table.insert(name.as_str().into(), 4000000000);
+        let data = table.to_binary();
+        let parser = crate::bin::ResTblReader::new(data.as_slice()).unwrap();
+        let mut buffer = vec![0u8; 173];
+        assert!(matches!(
+            parser.write_text_to_buf(&mut buffer),
+            Err(crate::Error::InsufficientBuffer(173, 175))
+        ));
+        let mut buffer = vec![0u8; 175];
+        let len = parser.write_text_to_buf(&mut buffer).unwrap();
+        assert_eq!(
+            &buffer[..len],
+            format!("\"{name}\": 4000000000\n").as_bytes()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn comments() {
+        let text =
+            "# Hand-edited patch\n\n1: 2\n  # Indented comment\n3:4\n\nTest/Name.bgyml:  5  \n";
+        let table = crate::ResourceSizeTable::from_text(text).unwrap();
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get(1), Some(2));
+        assert_eq!(table.get(3), Some(4));
+        assert_eq!(table.get("Test/Name.bgyml"), Some(5));
+        assert!(matches!(
+            crate::ResourceSizeTable::from_text("1: 2\nno colon"),
+            Err(crate::Error::YamlError(2, _))
+        ));
+        assert!(matches!(
+            crate::ResourceSizeTable::from_text("1: 2\n3: x"),
+            Err(crate::Error::YamlInvalidNumber(2, _))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn preview_text_patch() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let patch = "# Preview\nBake/Scene/MainField_G_26_43.bkres: 1\nTest/New.bgyml: 2\n";
+        let diff = table.preview_text_patch(patch).unwrap();
+        assert_eq!(
+            (diff.added.len(), diff.changed.len(), diff.removed.len()),
+            (1, 1, 0)
+        );
+        assert_eq!(table, crate::ResourceSizeTable::from_binary(DATA).unwrap());
+        let mut applied = table.clone();
+        applied.apply_text(patch).unwrap();
+        assert_eq!(table.diff(&applied), diff);
+        assert!(matches!(
+            table.preview_text_patch("1: 2\n3: x"),
+            Err(crate::Error::YamlInvalidNumber(2, _))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn crlf() {
+        let table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        let text = table.to_text_crlf();
+        assert_eq!(text.lines().count(), table.len());
+        assert_eq!(text.replace("\r\n", "\n"), table.to_text());
+        let parsed = crate::ResourceSizeTable::from_text(&text).unwrap();
+        assert!(table.diff(&parsed).is_empty());
+        let mut applied = crate::ResourceSizeTable::new();
+        applied
+            .apply_text("1: 2\r\n3: 4\r\nTest/Name.bgyml: 5\r")
+            .unwrap();
+        assert_eq!(applied.get(3), Some(4));
+        assert_eq!(applied.get("Test/Name.bgyml"), Some(5));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn roundtrip() {
+        crate::testutil::assert_text_roundtrip(DATA);
+        let mut table = crate::ResourceSizeTable::from_binary(DATA).unwrap();
+        assert!(table.text_roundtrip_diff().is_empty());
+        table.name_table.insert("Bad\nName".into(), 1);
+        assert_eq!(table.text_roundtrip_diff().removed.len(), table.len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn read_from_reader() {
+        let parser = crate::bin::ResTblReader::new(DATA).unwrap();
+        let text = parser.to_text();
+        let table = crate::ResourceSizeTable::from_text_reader(text.as_bytes()).unwrap();
+        assert_eq!(table, crate::ResourceSizeTable::from_text(&text).unwrap());
+        let err = crate::ResourceSizeTable::from_text_reader("1: 2\r\n3: 4\nbad\n".as_bytes())
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::YamlError(3, _)));
+    }
 }
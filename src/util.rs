@@ -1,3 +1,5 @@
+/// A resource name as stored in the RSTB name table: a NUL-padded, 160-byte
+/// UTF-8 string
 #[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(from = "&str"))]
 #[derive(Clone, Copy)]
@@ -47,9 +49,15 @@ impl PartialEq<&str> for &Name {
 
 impl Eq for Name {}
 
+impl core::hash::Hash for Name {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
 impl PartialOrd for Name {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        self.as_str().partial_cmp(other.as_str())
+        Some(self.cmp(other))
     }
 }
 
@@ -72,12 +80,37 @@ impl PartialOrd<&str> for &Name {
 }
 
 impl Name {
+    /// A name with no characters
+    pub(crate) const EMPTY: Self = Self { inner: [0; 160] };
+
+    /// Construct a name from a resource path, cleaning up dirty input so that
+    /// it hashes the same as the intended path. Trailing whitespace and NUL
+    /// bytes are removed first. The result must not contain a NUL byte, which
+    /// is never valid in a resource path and would end the name early, and
+    /// must fit in the 160-byte name field with its NUL terminator; otherwise
+    /// this fails with [`Error::NulInName`](crate::Error::NulInName) or
+    /// [`Error::NameTooLong`](crate::Error::NameTooLong). Converting with
+    /// `From<&str>` instead stores the path as is and silently truncates long
+    /// names.
+    pub fn new(name: &str) -> crate::Result<Self> {
+        let name = name.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+        if let Some(pos) = name.find('\0') {
+            Err(crate::Error::NulInName(pos))
+        } else if name.len() >= 160 {
+            Err(crate::Error::NameTooLong(name.len()))
+        } else {
+            Ok(Self::from(name))
+        }
+    }
+
     #[inline(always)]
     pub fn as_str(&self) -> &str {
-        unsafe {
-            let zero_idx = self.inner.iter().position(|c| *c == 0).unwrap_unchecked();
-            core::str::from_utf8_unchecked(&self.inner[..zero_idx])
-        }
+        let len = self
+            .inner
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(self.inner.len());
+        unsafe { core::str::from_utf8_unchecked(&self.inner[..len]) }
     }
 }
 
@@ -89,10 +122,15 @@ impl AsRef<str> for Name {
 }
 
 impl From<&str> for Name {
+    /// Truncates names longer than 160 bytes, backing off to a character
+    /// boundary so the stored name stays valid UTF-8
     fn from(value: &str) -> Self {
         let mut inner: [u8; 160] = unsafe { core::mem::zeroed() };
-        let len = value.len().min(160);
-        inner[..len].copy_from_slice(value.as_bytes());
+        let mut len = value.len().min(160);
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        inner[..len].copy_from_slice(&value.as_bytes()[..len]);
         Self { inner }
     }
 }
@@ -139,6 +177,60 @@ pub(crate) fn read_u32(value: &[u8], offset: Option<usize>) -> crate::Result<u32
     }
 }
 
+/// The magic number starting every zstd frame
+pub(crate) const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Write a file by way of a temporary file beside it, which is renamed over
+/// the destination only once `write` has succeeded and the data is synced, so
+/// an interrupted write never leaves the destination truncated. The temporary
+/// file is removed if anything fails.
+#[cfg(feature = "std")]
+pub(crate) fn write_atomic(
+    path: &std::path::Path,
+    write: impl FnOnce(&mut std::io::BufWriter<std::fs::File>) -> crate::Result<()>,
+) -> crate::Result<()> {
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(".tmp");
+    let temp = path.with_file_name(temp_name);
+    let result = (|| {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&temp)?);
+        write(&mut file)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Read the decompressed content size from a zstd frame header, if the frame
+/// records one.
+#[cfg(feature = "std")]
+pub(crate) fn zstd_content_size(data: &[u8]) -> Option<u64> {
+    if data.len() < 5 || data[..4] != ZSTD_MAGIC {
+        return None;
+    }
+    let descriptor = data[4];
+    let single_segment = descriptor & 0x20 != 0;
+    let fcs_size = match descriptor >> 6 {
+        0 if single_segment => 1,
+        0 => return None,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    let dict_id_size = [0, 1, 2, 4][(descriptor & 0x3) as usize];
+    let start = 5 + usize::from(!single_segment) + dict_id_size;
+    let field = data.get(start..start + fcs_size)?;
+    let mut bytes = [0u8; 8];
+    bytes[..fcs_size].copy_from_slice(field);
+    let size = u64::from_le_bytes(bytes);
+    Some(if fcs_size == 2 { size + 256 } else { size })
+}
+
 /// CRC hash function matching that used in BOTW/TOTK.
 #[inline]
 pub const fn hash_name(name: &str) -> u32 {
@@ -159,3 +251,41 @@ pub const fn hash_name(name: &str) -> u32 {
     }
     !crc
 }
+
+/// A CRC32 variant used to hash resource names
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgo {
+    /// The CRC32 used by BOTW/TOTK, as computed by [`hash_name`]
+    #[default]
+    Crc32Totk,
+    /// A reflected (LSB-first) CRC32 with a custom polynomial, initial value,
+    /// and final XOR. TOTK's own hash is `poly: 0xEDB88320`,
+    /// `init: 0xFFFFFFFF`, `xorout: 0xFFFFFFFF`.
+    Custom { poly: u32, init: u32, xorout: u32 },
+}
+
+/// Hash a resource name with the given algorithm. [`HashAlgo::Crc32Totk`] is
+/// identical to [`hash_name`].
+#[inline]
+pub const fn hash_name_with(algo: HashAlgo, name: &str) -> u32 {
+    let (poly, init, xorout) = match algo {
+        HashAlgo::Crc32Totk => return hash_name(name),
+        HashAlgo::Custom { poly, init, xorout } => (poly, init, xorout),
+    };
+    let mut crc = init;
+    let mut i = 0;
+    while i < name.len() {
+        crc ^= name.as_bytes()[i] as u32;
+        let mut j = 0;
+        while j < 8 {
+            if crc & 1 == 1 {
+                crc = (crc >> 1) ^ poly;
+            } else {
+                crc >>= 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    crc ^ xorout
+}